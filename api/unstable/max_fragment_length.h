@@ -0,0 +1,42 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License").
+ * You may not use this file except in compliance with the License.
+ * A copy of the License is located at
+ *
+ *  http://aws.amazon.com/apache2.0
+ *
+ * or in the "license" file accompanying this file. This file is distributed
+ * on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ * express or implied. See the License for the specific language governing
+ * permissions and limitations under the License.
+ */
+
+#pragma once
+
+#include <s2n.h>
+
+/**
+ * @file max_fragment_length.h
+ *
+ * The TLS Maximum Fragment Length Extension (https://www.rfc-editor.org/rfc/rfc6066#section-4)
+ * lets constrained clients ask the server to send smaller records.
+ * Clients request a length with s2n_config_send_max_fragment_length(),
+ * and servers opt in to accepting the request with s2n_config_accept_max_fragment_length().
+ */
+
+/**
+ * Retrieves the maximum fragment length negotiated for the connection.
+ *
+ * A maximum fragment length is only negotiated if the client requests one
+ * and the server accepts the request. If no length was negotiated,
+ * `negotiated` is set to false and `mfl_code` is not modified.
+ *
+ * @param conn A pointer to the connection
+ * @param negotiated Set to true if a maximum fragment length was negotiated
+ * @param mfl_code Set to the negotiated maximum fragment length
+ * @returns S2N_SUCCESS on success. S2N_FAILURE on failure
+ */
+S2N_API int s2n_connection_get_negotiated_max_fragment_length(struct s2n_connection *conn, bool *negotiated,
+        s2n_max_frag_len *mfl_code);
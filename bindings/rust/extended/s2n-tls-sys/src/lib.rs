@@ -17,6 +17,7 @@ pub mod s2n_status_code {
     pub type Type = libc::c_int;
     pub const SUCCESS: Type = 0;
     pub const FAILURE: Type = -1;
+    pub const CALLBACK_BLOCKED: Type = -2;
 }
 
 pub mod s2n_tls_version {
@@ -0,0 +1,80 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    io,
+    os::unix::io::AsRawFd,
+    task::{Context, Poll},
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, Interest},
+    net::{TcpStream, UnixStream},
+};
+
+/// A socket that can be handed to the kernel for kTLS.
+///
+/// After kTLS is enabled, s2n-tls reads from and writes to the socket's file
+/// descriptor directly, so the stream must not buffer any data itself.
+/// The stream is only used to wait for the socket to become ready.
+pub trait KtlsStream: AsyncRead + AsyncWrite + Unpin + AsRawFd {
+    /// Polls for the socket to become ready for the given interest.
+    ///
+    /// See [TcpStream::poll_read_ready] and [TcpStream::poll_write_ready].
+    fn poll_ready(&self, ctx: &mut Context, interest: Interest) -> Poll<io::Result<()>>;
+
+    /// Performs IO on the socket, clearing its readiness if the IO would block.
+    ///
+    /// See [TcpStream::try_io].
+    fn try_io(
+        &self,
+        interest: Interest,
+        action: &mut dyn FnMut() -> io::Result<()>,
+    ) -> io::Result<()>;
+}
+
+macro_rules! impl_ktls_stream {
+    ($stream:ty) => {
+        impl KtlsStream for $stream {
+            fn poll_ready(&self, ctx: &mut Context, interest: Interest) -> Poll<io::Result<()>> {
+                if interest.is_readable() {
+                    self.poll_read_ready(ctx)
+                } else {
+                    self.poll_write_ready(ctx)
+                }
+            }
+
+            fn try_io(
+                &self,
+                interest: Interest,
+                action: &mut dyn FnMut() -> io::Result<()>,
+            ) -> io::Result<()> {
+                <$stream>::try_io(self, interest, action)
+            }
+        }
+    };
+}
+
+impl_ktls_stream!(TcpStream);
+impl_ktls_stream!(UnixStream);
+
+/// The most bytes requested from [`s2n_tls::connection::Connection::poll_sendfile`] at once.
+///
+/// s2n-tls checks the key usage limits against the full count before sending,
+/// and the kernel never sends more than about 2GB in a single call anyway.
+pub(crate) const SENDFILE_CHUNK_SIZE: usize = 1 << 20;
+
+/// Recovers the [`KtlsStream`] implementation of a stream.
+///
+/// The methods of [`crate::TlsStream`] aren't bound by [`KtlsStream`], so this
+/// is captured when kTLS is enabled and stored for later IO.
+pub(crate) type AsKtlsStream<S> = fn(&S) -> &dyn KtlsStream;
+
+/// The directions of a [`crate::TlsStream`] that were handed to the kernel.
+///
+/// Once either direction uses kTLS, s2n-tls performs all IO on the socket's
+/// file descriptor, so the stream is only used to wait for readiness.
+pub(crate) struct KtlsState<S> {
+    pub(crate) as_stream: AsKtlsStream<S>,
+    pub(crate) send: bool,
+    pub(crate) recv: bool,
+}
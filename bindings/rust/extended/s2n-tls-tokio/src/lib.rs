@@ -20,10 +20,21 @@ use std::{
     },
 };
 use tokio::{
-    io::{AsyncRead, AsyncWrite, ReadBuf},
-    time::{sleep, Duration, Sleep},
+    io::{AsyncRead, AsyncWrite, Interest, ReadBuf},
+    time::{sleep, Duration, Instant, Sleep},
 };
 
+#[cfg(all(feature = "unstable-ktls", unix))]
+mod ktls;
+#[cfg(all(feature = "unstable-ktls", unix))]
+pub use ktls::KtlsStream;
+#[cfg(all(feature = "unstable-ktls", unix))]
+use std::{future::poll_fn, ops::Range, os::unix::io::AsRawFd};
+
+mod stats;
+use stats::StreamCounters;
+pub use stats::TlsStreamStats;
+
 // TODO use the version from s2n_quic_core
 mod task;
 use task::waker::debug_assert_contract as debug_assert_waker_contract;
@@ -154,6 +165,9 @@ where
     stream: S,
     blinding: Option<Pin<Box<Sleep>>>,
     shutdown_error: Option<Error>,
+    counters: StreamCounters,
+    #[cfg(all(feature = "unstable-ktls", unix))]
+    ktls: Option<ktls::KtlsState<S>>,
 }
 
 impl<S, C> TlsStream<S, C>
@@ -177,15 +191,52 @@ where
             stream,
             blinding: None,
             shutdown_error: None,
+            counters: StreamCounters::default(),
+            #[cfg(all(feature = "unstable-ktls", unix))]
+            ktls: None,
         };
+        let start = Instant::now();
         TlsHandshake {
             tls: &mut tls,
             error: None,
         }
         .await?;
+        tls.counters.handshake_duration = start.elapsed();
         Ok(tls)
     }
 
+    /// Reports how much data has been sent and received, and how much of it
+    /// was TLS overhead.
+    ///
+    /// The wire byte counts correspond to [Connection::wire_bytes_in]
+    /// and [Connection::wire_bytes_out], until kTLS is enabled.
+    pub fn stats(&self) -> TlsStreamStats {
+        let conn = self.conn.as_ref();
+        TlsStreamStats {
+            wire_bytes_in: self
+                .counters
+                .ktls_wire_bytes_in
+                .unwrap_or_else(|| conn.wire_bytes_in()),
+            wire_bytes_out: self
+                .counters
+                .ktls_wire_bytes_out
+                .unwrap_or_else(|| conn.wire_bytes_out()),
+            app_bytes_in: self.counters.app_bytes_in,
+            app_bytes_out: self.counters.app_bytes_out,
+            records_in: self.counters.records_in.count(),
+            records_out: self.counters.records_out.count(),
+            handshake_duration: self.counters.handshake_duration,
+        }
+    }
+
+    /// Counts application data successfully sent by the connection.
+    fn count_sent(&mut self, poll: Poll<Result<usize, Error>>) -> Poll<io::Result<usize>> {
+        if let Ready(Ok(sent)) = poll {
+            self.counters.app_bytes_out += sent as u64;
+        }
+        poll.map_err(io::Error::from)
+    }
+
     fn with_io<F, R>(&mut self, ctx: &mut Context, action: F) -> Poll<Result<R, Error>>
     where
         F: FnOnce(Pin<&mut Self>) -> Poll<Result<R, Error>>,
@@ -215,6 +266,63 @@ where
         }
     }
 
+    /// Performs IO directly on the socket if kTLS was enabled.
+    ///
+    /// Returns `None` if kTLS was not enabled, in which case [`Self::with_io`]
+    /// should be used instead.
+    #[cfg(all(feature = "unstable-ktls", unix))]
+    fn poll_ktls_io<F, R>(
+        &mut self,
+        ctx: &mut Context,
+        interest: Interest,
+        mut action: F,
+    ) -> Option<Poll<Result<R, Error>>>
+    where
+        F: FnMut(&mut Connection) -> Poll<Result<R, Error>>,
+    {
+        let as_ktls = self.ktls.as_ref()?.as_stream;
+        let stream = as_ktls(&self.stream);
+        let conn = self.conn.as_mut();
+        loop {
+            match stream.poll_ready(ctx, interest) {
+                Ready(Ok(())) => {}
+                Ready(Err(err)) => return Some(Ready(Err(Error::io_error(err)))),
+                Pending => return Some(Pending),
+            };
+
+            // s2n-tls performs the IO itself, so report when it would block
+            // in order to clear the socket's readiness.
+            let mut result = None;
+            let io_result = stream.try_io(interest, &mut || match action(conn) {
+                Ready(r) => {
+                    result = Some(r);
+                    Ok(())
+                }
+                Pending => Err(io::ErrorKind::WouldBlock.into()),
+            });
+
+            match (result, io_result) {
+                (Some(result), _) => return Some(Ready(result)),
+                (None, Err(err)) if err.kind() == io::ErrorKind::WouldBlock => continue,
+                (None, Err(err)) => return Some(Ready(Err(Error::io_error(err)))),
+                (None, Ok(())) => unreachable!("successful IO always produces a result"),
+            }
+        }
+    }
+
+    #[cfg(not(all(feature = "unstable-ktls", unix)))]
+    fn poll_ktls_io<F, R>(
+        &mut self,
+        _ctx: &mut Context,
+        _interest: Interest,
+        _action: F,
+    ) -> Option<Poll<Result<R, Error>>>
+    where
+        F: FnMut(&mut Connection) -> Poll<Result<R, Error>>,
+    {
+        None
+    }
+
     fn poll_io<F>(ctx: *mut c_void, action: F) -> c_int
     where
         F: FnOnce(Pin<&mut S>, &mut Context) -> Poll<Result<usize, std::io::Error>>,
@@ -240,19 +348,31 @@ where
     }
 
     unsafe extern "C" fn recv_io_cb(ctx: *mut c_void, buf: *mut u8, len: u32) -> c_int {
-        Self::poll_io(ctx, |stream, async_context| {
+        let result = Self::poll_io(ctx, |stream, async_context| {
             let mut dest = ReadBuf::new(std::slice::from_raw_parts_mut(buf, len as usize));
             stream
                 .poll_read(async_context, &mut dest)
                 .map_ok(|_| dest.filled().len())
-        })
+        });
+        if result > 0 {
+            let tls = &mut *(ctx as *mut Self);
+            let received = std::slice::from_raw_parts(buf, result as usize);
+            tls.counters.records_in.update(received);
+        }
+        result
     }
 
     unsafe extern "C" fn send_io_cb(ctx: *mut c_void, buf: *const u8, len: u32) -> c_int {
-        Self::poll_io(ctx, |stream, async_context| {
+        let result = Self::poll_io(ctx, |stream, async_context| {
             let src = std::slice::from_raw_parts(buf, len as usize);
             stream.poll_write(async_context, src)
-        })
+        });
+        if result > 0 {
+            let tls = &mut *(ctx as *mut Self);
+            let sent = std::slice::from_raw_parts(buf, result as usize);
+            tls.counters.records_out.update(sent);
+        }
+        result
     }
 
     /// Polls the blinding timer, if there is any.
@@ -295,6 +415,135 @@ where
     }
 }
 
+#[cfg(all(feature = "unstable-ktls", unix))]
+impl<S, C> TlsStream<S, C>
+where
+    C: AsRef<Connection> + AsMut<Connection> + Unpin,
+    S: KtlsStream,
+{
+    /// Hands the socket to the kernel for kTLS.
+    ///
+    /// After kTLS is enabled, s2n-tls reads from and writes to the socket
+    /// directly instead of through the stream's [`AsyncRead`] and [`AsyncWrite`]
+    /// implementations. Use [Error::ktls_error](`s2n_tls::error::Error::ktls_error()`)
+    /// to determine why kTLS could not be enabled. If enabling kTLS fails, the
+    /// stream remains usable, but records are encrypted or decrypted by s2n-tls.
+    ///
+    /// Both directions are attempted before an error is returned, and a
+    /// direction handed to the kernel can't be taken back. So an error may
+    /// leave only one direction using kTLS: use [`Self::ktls_send_enabled`]
+    /// and [`Self::ktls_recv_enabled`] to check. Calling this method again
+    /// only retries the directions that aren't enabled yet.
+    ///
+    /// Must be called after the handshake completes, when there is no
+    /// buffered application data to send or receive.
+    ///
+    /// Corresponds to [Connection::enable_ktls_send] and [Connection::enable_ktls_recv].
+    pub fn enable_ktls(&mut self) -> Result<(), Error> {
+        // Once a direction is enabled, the connection uses kTLS IO callbacks
+        // for it, which setting the file descriptor again would replace.
+        if self.ktls.is_none() {
+            let fd = self.stream.as_raw_fd();
+            self.conn.as_mut().set_fd(fd)?;
+        }
+        // The connection stops counting wire bytes once kTLS is enabled,
+        // so keep the final counts for stats().
+        let wire_bytes_in = self.conn.as_ref().wire_bytes_in();
+        let wire_bytes_out = self.conn.as_ref().wire_bytes_out();
+        let send = self.conn.as_mut().enable_ktls_send().map(|_| ());
+        let recv = self.conn.as_mut().enable_ktls_recv().map(|_| ());
+        if send.is_ok() {
+            self.counters
+                .ktls_wire_bytes_out
+                .get_or_insert(wire_bytes_out);
+        }
+        if recv.is_ok() {
+            self.counters
+                .ktls_wire_bytes_in
+                .get_or_insert(wire_bytes_in);
+        }
+        // Only switch the stream to the socket once both directions were
+        // attempted. If neither was enabled, nothing was handed to the kernel
+        // and the IO callbacks will replace the file descriptor on the next IO call.
+        if send.is_ok() || recv.is_ok() {
+            self.ktls = Some(ktls::KtlsState {
+                as_stream: |stream: &S| -> &dyn KtlsStream { stream },
+                send: send.is_ok(),
+                recv: recv.is_ok(),
+            });
+        }
+        send.and(recv)
+    }
+
+    /// Returns whether records sent by the stream are encrypted by the kernel.
+    ///
+    /// See [`Self::enable_ktls`].
+    pub fn ktls_send_enabled(&self) -> bool {
+        self.ktls.as_ref().is_some_and(|ktls| ktls.send)
+    }
+
+    /// Returns whether records received by the stream are decrypted by the kernel.
+    ///
+    /// See [`Self::enable_ktls`].
+    pub fn ktls_recv_enabled(&self) -> bool {
+        self.ktls.as_ref().is_some_and(|ktls| ktls.recv)
+    }
+
+    /// Attempts to send up to `count` bytes of a file, starting at `offset`.
+    ///
+    /// Only supported once [`Self::enable_ktls`] succeeds in enabling kTLS
+    /// for sending. Returns the number of bytes sent, which may be less than
+    /// `count`. 0 bytes returned indicates the end of the file.
+    ///
+    /// Corresponds to [Connection::poll_sendfile].
+    pub fn poll_sendfile<F: AsRawFd>(
+        &mut self,
+        ctx: &mut Context,
+        file: &F,
+        offset: u64,
+        count: usize,
+    ) -> Poll<Result<usize, Error>> {
+        let fd = file.as_raw_fd();
+        let mut sendfile = |conn: &mut Connection| conn.poll_sendfile(fd, offset, count);
+        let poll = match self.poll_ktls_io(ctx, Interest::WRITABLE, &mut sendfile) {
+            Some(poll) => poll,
+            // s2n-tls will report that kTLS is required.
+            None => self.with_io(ctx, |mut context| sendfile(context.conn.as_mut())),
+        };
+        if let Ready(Ok(sent)) = poll {
+            self.counters.app_bytes_out += sent as u64;
+        }
+        poll
+    }
+
+    /// Sends the contents of `range` from a file without copying them into userspace.
+    ///
+    /// Large ranges are sent in bounded chunks. Returns the total number of
+    /// bytes sent, which is only less than the length of `range` if the end
+    /// of the file was reached.
+    ///
+    /// See [`Self::poll_sendfile`].
+    pub async fn sendfile<F: AsRawFd>(
+        &mut self,
+        file: &F,
+        range: Range<u64>,
+    ) -> Result<usize, Error> {
+        let mut offset = range.start;
+        while offset < range.end {
+            let count = (range.end - offset)
+                .try_into()
+                .unwrap_or(usize::MAX)
+                .min(ktls::SENDFILE_CHUNK_SIZE);
+            let sent = poll_fn(|ctx| self.poll_sendfile(ctx, file, offset, count)).await?;
+            if sent == 0 {
+                break;
+            }
+            offset += sent as u64;
+        }
+        Ok((offset - range.start) as usize)
+    }
+}
+
 impl<S, C> AsRef<Connection> for TlsStream<S, C>
 where
     C: AsRef<Connection> + AsMut<Connection> + Unpin,
@@ -326,13 +575,11 @@ where
         buf: &mut ReadBuf<'_>,
     ) -> Poll<io::Result<()>> {
         let tls = self.get_mut();
-        tls.with_io(ctx, |mut context| {
-            context
-                .conn
-                .as_mut()
-                // Safe since poll_recv_uninitialized does not
-                // deinitialize any bytes.
-                .poll_recv_uninitialized(unsafe { buf.unfilled_mut() })
+        let filled = buf.filled().len();
+        let mut recv = |conn: &mut Connection| {
+            // Safe since poll_recv_uninitialized does not
+            // deinitialize any bytes.
+            conn.poll_recv_uninitialized(unsafe { buf.unfilled_mut() })
                 .map_ok(|size| {
                     unsafe {
                         // Safe since poll_recv_uninitialized guaranteed
@@ -342,8 +589,16 @@ where
                     }
                     buf.advance(size);
                 })
-        })
-        .map_err(io::Error::from)
+        };
+
+        let poll = match tls.poll_ktls_io(ctx, Interest::READABLE, &mut recv) {
+            Some(poll) => poll,
+            None => tls.with_io(ctx, |mut context| recv(context.conn.as_mut())),
+        };
+        if let Ready(Ok(())) = poll {
+            tls.counters.app_bytes_in += (buf.filled().len() - filled) as u64;
+        }
+        poll.map_err(io::Error::from)
     }
 }
 
@@ -358,17 +613,44 @@ where
         buf: &[u8],
     ) -> Poll<io::Result<usize>> {
         let tls = self.get_mut();
-        tls.with_io(ctx, |mut context| context.conn.as_mut().poll_send(buf))
-            .map_err(io::Error::from)
+
+        let poll = match tls.poll_ktls_io(ctx, Interest::WRITABLE, |conn| conn.poll_send(buf)) {
+            Some(poll) => poll,
+            None => tls.with_io(ctx, |mut context| context.conn.as_mut().poll_send(buf)),
+        };
+        tls.count_sent(poll)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let tls = self.get_mut();
+        let mut send = |conn: &mut Connection| conn.poll_send_vectored(bufs);
+
+        let poll = match tls.poll_ktls_io(ctx, Interest::WRITABLE, &mut send) {
+            Some(poll) => poll,
+            None => tls.with_io(ctx, |mut context| send(context.conn.as_mut())),
+        };
+        tls.count_sent(poll)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        true
     }
 
     fn poll_flush(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<io::Result<()>> {
         let tls = self.get_mut();
+        let mut flush = |conn: &mut Connection| conn.poll_flush().map(|r| r.map(|_| ()));
+
+        if let Some(poll) = tls.poll_ktls_io(ctx, Interest::WRITABLE, &mut flush) {
+            ready!(poll).map_err(io::Error::from)?;
+            return Pin::new(&mut tls.stream).poll_flush(ctx);
+        }
 
-        ready!(tls.with_io(ctx, |mut context| {
-            context.conn.as_mut().poll_flush().map(|r| r.map(|_| ()))
-        }))
-        .map_err(io::Error::from)?;
+        ready!(tls.with_io(ctx, |mut context| flush(context.conn.as_mut())))
+            .map_err(io::Error::from)?;
 
         Pin::new(&mut tls.stream).poll_flush(ctx)
     }
@@ -379,14 +661,15 @@ where
 
             // s2n_shutdown_send must not be called again if it errors
             if self.shutdown_error.is_none() {
-                let result = ready!(self.as_mut().with_io(ctx, |mut context| {
-                    context
-                        .conn
-                        .as_mut()
-                        .poll_shutdown_send()
-                        .map(|r| r.map(|_| ()))
-                }));
-                if let Err(error) = result {
+                let tls = self.as_mut().get_mut();
+                let mut shutdown_send =
+                    |conn: &mut Connection| conn.poll_shutdown_send().map(|r| r.map(|_| ()));
+
+                let poll = match tls.poll_ktls_io(ctx, Interest::WRITABLE, &mut shutdown_send) {
+                    Some(poll) => poll,
+                    None => tls.with_io(ctx, |mut context| shutdown_send(context.conn.as_mut())),
+                };
+                if let Err(error) = ready!(poll) {
                     self.shutdown_error = Some(error);
                     // s2n_shutdown_send only writes, so will never trigger blinding again.
                     // So we do not need to poll_blinding again after this error.
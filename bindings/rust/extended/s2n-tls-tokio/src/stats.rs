@@ -0,0 +1,131 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+/// Traffic statistics for a [`TlsStream`](crate::TlsStream).
+///
+/// The wire counts include all TLS overhead: record headers, encryption
+/// overhead, handshake messages, and alerts. The application counts only
+/// include the plaintext read from or written to the stream, so the
+/// difference between the two is the cost of TLS.
+///
+/// Once kTLS is enabled, the kernel handles records directly, so the wire
+/// and record counts for that direction keep the values they had when kTLS
+/// was enabled. Later traffic is only reflected in the application counts.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TlsStreamStats {
+    /// Bytes received from the underlying stream.
+    pub wire_bytes_in: u64,
+    /// Bytes sent on the underlying stream.
+    pub wire_bytes_out: u64,
+    /// Bytes of application data read from the TLS stream.
+    pub app_bytes_in: u64,
+    /// Bytes of application data written to the TLS stream.
+    pub app_bytes_out: u64,
+    /// TLS records received, of any content type.
+    pub records_in: u64,
+    /// TLS records sent, of any content type.
+    pub records_out: u64,
+    /// How long the TLS handshake took to complete.
+    pub handshake_duration: Duration,
+}
+
+/// Counts TLS records by tracking the record headers in a stream of bytes.
+///
+/// s2n-tls doesn't report how many records it sends or receives, and the
+/// IO callbacks may see partial records or several records at once,
+/// so the record headers are tracked across calls.
+/// A peer that sends an SSLv2 ClientHello will cause an inaccurate count,
+/// because that message doesn't use a TLS record header.
+#[derive(Debug, Default)]
+pub(crate) struct RecordCounter {
+    header: [u8; Self::HEADER_LEN],
+    header_len: usize,
+    remaining: usize,
+    count: u64,
+}
+
+impl RecordCounter {
+    /// Content type (1) + protocol version (2) + length (2)
+    const HEADER_LEN: usize = 5;
+
+    pub(crate) fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub(crate) fn update(&mut self, mut bytes: &[u8]) {
+        while !bytes.is_empty() {
+            if self.remaining > 0 {
+                let skipped = self.remaining.min(bytes.len());
+                self.remaining -= skipped;
+                bytes = &bytes[skipped..];
+                continue;
+            }
+
+            let copied = (Self::HEADER_LEN - self.header_len).min(bytes.len());
+            self.header[self.header_len..][..copied].copy_from_slice(&bytes[..copied]);
+            self.header_len += copied;
+            bytes = &bytes[copied..];
+
+            if self.header_len == Self::HEADER_LEN {
+                let [_, _, _, len_hi, len_lo] = self.header;
+                self.remaining = u16::from_be_bytes([len_hi, len_lo]) as usize;
+                self.header_len = 0;
+                self.count += 1;
+            }
+        }
+    }
+}
+
+/// Counters that the connection doesn't track itself.
+#[derive(Debug, Default)]
+pub(crate) struct StreamCounters {
+    pub(crate) app_bytes_in: u64,
+    pub(crate) app_bytes_out: u64,
+    pub(crate) records_in: RecordCounter,
+    pub(crate) records_out: RecordCounter,
+    pub(crate) handshake_duration: Duration,
+    /// The connection's wire counts when kTLS was enabled for each direction,
+    /// after which the connection always reports 0.
+    pub(crate) ktls_wire_bytes_in: Option<u64>,
+    pub(crate) ktls_wire_bytes_out: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(len: u16) -> Vec<u8> {
+        let mut record = vec![23, 3, 3];
+        record.extend_from_slice(&len.to_be_bytes());
+        record.resize(record.len() + len as usize, 0);
+        record
+    }
+
+    #[test]
+    fn count_records() {
+        let mut bytes = record(100);
+        bytes.extend(record(0));
+        bytes.extend(record(u16::MAX));
+        bytes.extend(record(1));
+
+        let mut counter = RecordCounter::default();
+        counter.update(&bytes);
+        assert_eq!(counter.count(), 4);
+
+        // Records and headers may be split across any number of calls
+        let mut counter = RecordCounter::default();
+        for byte in bytes.chunks(1) {
+            counter.update(byte);
+        }
+        assert_eq!(counter.count(), 4);
+
+        let mut counter = RecordCounter::default();
+        counter.update(&bytes[..2]);
+        assert_eq!(counter.count(), 0);
+        counter.update(&bytes[2..]);
+        assert_eq!(counter.count(), 4);
+    }
+}
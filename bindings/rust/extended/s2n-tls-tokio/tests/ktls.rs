@@ -0,0 +1,218 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(all(feature = "unstable-ktls", unix))]
+
+use s2n_tls::ktls::KtlsError;
+use s2n_tls_tokio::{KtlsStream, TlsAcceptor, TlsConnector, TlsStream};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+pub mod common;
+
+const TEST_DATA: &[u8] = "hello world".as_bytes();
+
+// The maximum TLS record payload is 2^14 bytes.
+// Send more to ensure multiple records.
+const LARGE_TEST_DATA: &[u8] = &[5; (1 << 15)];
+
+/// Enable kTLS, tolerating environments without kernel support.
+///
+/// Even if kTLS can't be enabled, the stream should continue to work.
+/// Returns whether kTLS was enabled.
+fn enable_ktls<S: KtlsStream>(stream: &mut TlsStream<S>) -> bool {
+    if let Err(error) = stream.enable_ktls() {
+        assert!(
+            matches!(
+                error.ktls_error(),
+                Some(KtlsError::UnsupportedPlatform) | Some(KtlsError::KernelRejected)
+            ),
+            "unexpected kTLS error: {error:?}"
+        );
+        return false;
+    }
+    assert!(stream.ktls_send_enabled());
+    assert!(stream.ktls_recv_enabled());
+    true
+}
+
+#[tokio::test]
+async fn ktls_send_and_recv() -> Result<(), Box<dyn std::error::Error>> {
+    let (server_stream, client_stream) = common::get_streams().await?;
+
+    let connector = TlsConnector::new(common::client_config_tls12()?.build()?);
+    let acceptor = TlsAcceptor::new(common::server_config_tls12()?.build()?);
+
+    let (mut client, mut server) =
+        common::run_negotiate(&connector, client_stream, &acceptor, server_stream).await?;
+    enable_ktls(&mut client);
+    enable_ktls(&mut server);
+
+    for data in [TEST_DATA, LARGE_TEST_DATA] {
+        client.write_all(data).await?;
+        let mut received = vec![0; data.len()];
+        server.read_exact(&mut received).await?;
+        assert_eq!(data, received);
+
+        server.write_all(data).await?;
+        let mut received = vec![0; data.len()];
+        client.read_exact(&mut received).await?;
+        assert_eq!(data, received);
+    }
+
+    client.shutdown().await?;
+    assert_eq!(server.read(&mut [0]).await?, 0);
+    server.shutdown().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn ktls_enable_twice() -> Result<(), Box<dyn std::error::Error>> {
+    let (server_stream, client_stream) = common::get_streams().await?;
+
+    let connector = TlsConnector::new(common::client_config_tls12()?.build()?);
+    let acceptor = TlsAcceptor::new(common::server_config_tls12()?.build()?);
+
+    let (mut client, mut server) =
+        common::run_negotiate(&connector, client_stream, &acceptor, server_stream).await?;
+    if !enable_ktls(&mut client) {
+        return Ok(());
+    }
+
+    // Enabling kTLS again must not replace the kTLS IO
+    client.enable_ktls()?;
+    assert!(client.ktls_send_enabled());
+    assert!(client.ktls_recv_enabled());
+
+    client.write_all(TEST_DATA).await?;
+    let mut received = vec![0; TEST_DATA.len()];
+    server.read_exact(&mut received).await?;
+    assert_eq!(TEST_DATA, received);
+
+    server.write_all(TEST_DATA).await?;
+    let mut received = vec![0; TEST_DATA.len()];
+    client.read_exact(&mut received).await?;
+    assert_eq!(TEST_DATA, received);
+
+    // The close_notify alert is sent through the kernel
+    client.shutdown().await?;
+    assert_eq!(server.read(&mut [0]).await?, 0);
+    server.shutdown().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn ktls_stats() -> Result<(), Box<dyn std::error::Error>> {
+    let (server_stream, client_stream) = common::get_streams().await?;
+
+    let connector = TlsConnector::new(common::client_config_tls12()?.build()?);
+    let acceptor = TlsAcceptor::new(common::server_config_tls12()?.build()?);
+
+    let (mut client, mut server) =
+        common::run_negotiate(&connector, client_stream, &acceptor, server_stream).await?;
+    let handshake = client.stats();
+    if !enable_ktls(&mut client) {
+        return Ok(());
+    }
+
+    client.write_all(TEST_DATA).await?;
+    server.read_exact(&mut vec![0; TEST_DATA.len()]).await?;
+    server.write_all(TEST_DATA).await?;
+    client.read_exact(&mut vec![0; TEST_DATA.len()]).await?;
+
+    // The kernel sends and receives the records, so the wire counts
+    // stay at their values from when kTLS was enabled.
+    let stats = client.stats();
+    assert_eq!(stats.wire_bytes_in, handshake.wire_bytes_in);
+    assert_eq!(stats.wire_bytes_out, handshake.wire_bytes_out);
+    assert_eq!(stats.records_in, handshake.records_in);
+    assert_eq!(stats.records_out, handshake.records_out);
+    assert_eq!(stats.app_bytes_in, TEST_DATA.len() as u64);
+    assert_eq!(stats.app_bytes_out, TEST_DATA.len() as u64);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn ktls_sendfile() -> Result<(), Box<dyn std::error::Error>> {
+    let (server_stream, client_stream) = common::get_streams().await?;
+
+    let connector = TlsConnector::new(common::client_config_tls12()?.build()?);
+    let acceptor = TlsAcceptor::new(common::server_config_tls12()?.build()?);
+
+    let (mut client, mut server) =
+        common::run_negotiate(&connector, client_stream, &acceptor, server_stream).await?;
+    if !enable_ktls(&mut client) {
+        return Ok(());
+    }
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/ktls.rs");
+    let expected = std::fs::read(path)?;
+    let file = std::fs::File::open(path)?;
+
+    // Send a range from the middle of the file
+    let range = 10..(expected.len() as u64 - 10);
+    let sent = client.sendfile(&file, range).await?;
+    assert_eq!(sent, expected.len() - 20);
+    let mut received = vec![0; sent];
+    server.read_exact(&mut received).await?;
+    assert_eq!(received, &expected[10..expected.len() - 10]);
+
+    // Sending past the end of the file stops at the end of the file
+    let sent = client.sendfile(&file, 0..u64::MAX).await?;
+    assert_eq!(sent, expected.len());
+    let mut received = vec![0; sent];
+    server.read_exact(&mut received).await?;
+    assert_eq!(received, expected);
+
+    // Ranges larger than a single sendfile call are sent in chunks
+    let path = std::env::temp_dir().join(format!("s2n-tls-tokio-sendfile-{}", std::process::id()));
+    let expected: Vec<u8> = (0..(5 << 19)).map(|i| i as u8).collect();
+    std::fs::write(&path, &expected)?;
+    let file = std::fs::File::open(&path)?;
+    std::fs::remove_file(&path)?;
+
+    let range = 1000..(expected.len() as u64 - 1000);
+    let expected = &expected[1000..expected.len() - 1000];
+    let mut received = vec![0; expected.len()];
+    let (sent, read) = tokio::join!(
+        client.sendfile(&file, range),
+        server.read_exact(&mut received)
+    );
+    read?;
+    let sent = sent?;
+    assert_eq!(sent, expected.len());
+    assert_eq!(received, expected);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn ktls_tls13_requires_opt_in() -> Result<(), Box<dyn std::error::Error>> {
+    let (server_stream, client_stream) = common::get_streams().await?;
+
+    let connector = TlsConnector::new(common::client_config()?.build()?);
+    let acceptor = TlsAcceptor::new(common::server_config()?.build()?);
+
+    let (mut client, mut server) =
+        common::run_negotiate(&connector, client_stream, &acceptor, server_stream).await?;
+
+    // TLS1.3 requires explicitly allowing kTLS with
+    // config::Builder::enable_ktls_unsafe_tls13
+    let error = client.enable_ktls().unwrap_err();
+    assert!(matches!(
+        error.ktls_error(),
+        Some(KtlsError::UnsupportedPlatform) | Some(KtlsError::UnsupportedConnection)
+    ));
+    assert!(!client.ktls_send_enabled());
+    assert!(!client.ktls_recv_enabled());
+
+    // The stream is still usable after the failure.
+    client.write_all(TEST_DATA).await?;
+    let mut received = [0; TEST_DATA.len()];
+    server.read_exact(&mut received).await?;
+    assert_eq!(TEST_DATA, received);
+
+    Ok(())
+}
@@ -2,8 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use s2n_tls_tokio::{TlsAcceptor, TlsConnector};
-use std::{io, task::Poll::*};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::{
+    io::{self, IoSlice},
+    task::Poll::*,
+};
+use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
 pub mod common;
 
@@ -75,6 +78,44 @@ async fn send_and_recv_multiple_records() -> Result<(), Box<dyn std::error::Erro
     Ok(())
 }
 
+#[tokio::test]
+async fn send_and_recv_vectored() -> Result<(), Box<dyn std::error::Error>> {
+    let (server_stream, client_stream) = common::get_streams().await?;
+
+    let connector = TlsConnector::new(common::client_config()?.build()?);
+    let acceptor = TlsAcceptor::new(common::server_config()?.build()?);
+
+    let (mut client, mut server) =
+        common::run_negotiate(&connector, client_stream, &acceptor, server_stream).await?;
+    assert!(client.is_write_vectored());
+
+    let header = TEST_DATA;
+    let body = LARGE_TEST_DATA;
+    let send = async {
+        let mut sent = 0;
+        while sent < header.len() + body.len() {
+            let bufs = if sent < header.len() {
+                [IoSlice::new(&header[sent..]), IoSlice::new(body)]
+            } else {
+                [
+                    IoSlice::new(&body[sent - header.len()..]),
+                    IoSlice::new(&[]),
+                ]
+            };
+            sent += client.write_vectored(&bufs).await?;
+        }
+        Ok::<(), io::Error>(())
+    };
+
+    let mut received = vec![0; header.len() + body.len()];
+    let (send_result, recv_result) = tokio::join!(send, server.read_exact(&mut received));
+    send_result?;
+    recv_result?;
+    assert_eq!(received, [header, body].concat());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn send_and_recv_split() -> Result<(), Box<dyn std::error::Error>> {
     let (server_stream, client_stream) = common::get_streams().await?;
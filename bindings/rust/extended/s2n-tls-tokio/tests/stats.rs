@@ -0,0 +1,71 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use s2n_tls_tokio::{TlsAcceptor, TlsConnector};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+pub mod common;
+
+// The maximum TLS record payload is 2^14 bytes.
+// Send more to ensure multiple records.
+const LARGE_TEST_DATA: &[u8] = &[5; (1 << 15)];
+
+#[tokio::test]
+async fn stats() -> Result<(), Box<dyn std::error::Error>> {
+    let (server_stream, client_stream) = common::get_streams().await?;
+
+    let connector = TlsConnector::new(common::client_config()?.build()?);
+    let acceptor = TlsAcceptor::new(common::server_config()?.build()?);
+
+    let (mut client, mut server) =
+        common::run_negotiate(&connector, client_stream, &acceptor, server_stream).await?;
+
+    // Only the handshake has been sent so far
+    let client_handshake = client.stats();
+    let server_handshake = server.stats();
+    for stats in [client_handshake, server_handshake] {
+        assert_eq!(stats.app_bytes_in, 0);
+        assert_eq!(stats.app_bytes_out, 0);
+        assert!(stats.wire_bytes_in > 0);
+        assert!(stats.wire_bytes_out > 0);
+        assert!(stats.records_in > 0);
+        assert!(stats.records_out > 0);
+        assert!(!stats.handshake_duration.is_zero());
+    }
+    assert_eq!(
+        client_handshake.wire_bytes_out,
+        server_handshake.wire_bytes_in
+    );
+    assert_eq!(client_handshake.records_out, server_handshake.records_in);
+    assert_eq!(
+        server_handshake.wire_bytes_out,
+        client_handshake.wire_bytes_in
+    );
+    assert_eq!(server_handshake.records_out, client_handshake.records_in);
+
+    client.write_all(LARGE_TEST_DATA).await?;
+    let mut received = vec![0; LARGE_TEST_DATA.len()];
+    server.read_exact(&mut received).await?;
+
+    let client_stats = client.stats();
+    let server_stats = server.stats();
+    let len = LARGE_TEST_DATA.len() as u64;
+    assert_eq!(client_stats.app_bytes_out, len);
+    assert_eq!(server_stats.app_bytes_in, len);
+
+    // Every record adds overhead
+    let wire_bytes = client_stats.wire_bytes_out - client_handshake.wire_bytes_out;
+    let records = client_stats.records_out - client_handshake.records_out;
+    assert!(records >= 2);
+    assert!(wire_bytes > len);
+    assert_eq!(client_stats.wire_bytes_out, server_stats.wire_bytes_in);
+    assert_eq!(client_stats.records_out, server_stats.records_in);
+
+    // The handshake duration doesn't change after the handshake
+    assert_eq!(
+        client_stats.handshake_duration,
+        client_handshake.handshake_duration
+    );
+
+    Ok(())
+}
@@ -29,15 +29,32 @@ use s2n_tls_sys::s2n_connection;
 mod async_cb;
 pub use async_cb::*;
 
+mod cert_tiebreak;
+pub use cert_tiebreak::*;
+
 mod client_hello;
 pub use client_hello::*;
 
+mod early_data;
+pub use early_data::*;
+
+mod psk_selection;
+pub use psk_selection::*;
+
+mod session_cache;
+pub use session_cache::*;
+
 mod session_ticket;
 pub use session_ticket::*;
 
 mod pkey;
 pub use pkey::*;
 
+#[cfg(feature = "unstable-async_offload")]
+mod async_offload;
+#[cfg(feature = "unstable-async_offload")]
+pub use async_offload::*;
+
 #[cfg(feature = "unstable-crl")]
 mod cert_validation;
 #[cfg(feature = "unstable-crl")]
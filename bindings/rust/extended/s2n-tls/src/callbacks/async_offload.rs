@@ -0,0 +1,371 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for offloading expensive handshake operations to the application.
+
+use crate::{
+    callbacks::*,
+    connection::Connection,
+    enums::CallbackResult,
+    error::{Error, ErrorType, Fallible},
+};
+use core::task::Poll;
+use s2n_tls_sys::*;
+use std::{
+    pin::Pin,
+    ptr::NonNull,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+const CANCELLED: Error = Error::bindings(
+    ErrorType::UsageError,
+    "AsyncOffloadError",
+    "the connection no longer expects the offloaded operation",
+);
+
+const FAILED: Error = Error::bindings(
+    ErrorType::ProtocolError,
+    "AsyncOffloadError",
+    "the offloaded operation failed",
+);
+
+/// The kinds of operations that can be offloaded.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OffloadOperationType {
+    /// Verifying the signature sent by the peer to prove ownership of its certificate.
+    PkeyVerify,
+}
+
+impl From<OffloadOperationType> for s2n_async_offload_op_type::Type {
+    fn from(input: OffloadOperationType) -> Self {
+        match input {
+            OffloadOperationType::PkeyVerify => s2n_async_offload_op_type::OFFLOAD_PKEY_VERIFY,
+        }
+    }
+}
+
+struct OperationPtr(NonNull<s2n_async_offload_op>);
+
+/// # Safety
+///
+/// Safety: s2n_async_offload_op objects can be performed from any thread,
+/// and access to the pointer is synchronized by the [`OperationState`] mutex.
+unsafe impl Send for OperationPtr {}
+
+enum OperationState {
+    Pending(OperationPtr),
+    Performed { success: bool },
+    Cancelled,
+}
+
+type SharedState = Arc<Mutex<OperationState>>;
+
+fn lock(state: &SharedState) -> MutexGuard<OperationState> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Corresponds to [s2n_async_offload_op].
+///
+/// Unlike most handshake state, an operation may be performed from any thread,
+/// such as a blocking thread pool.
+pub struct AsyncOffloadOperation {
+    state: SharedState,
+}
+
+impl AsyncOffloadOperation {
+    fn new(op: OperationPtr) -> Self {
+        let state = Arc::new(Mutex::new(OperationState::Pending(op)));
+        AsyncOffloadOperation { state }
+    }
+
+    /// Performs the operation.
+    ///
+    /// Fails if the connection that requested the operation
+    /// has since been dropped or wiped.
+    ///
+    /// Corresponds to [s2n_async_offload_op_perform].
+    pub fn perform(self) -> Result<(), Error> {
+        let mut state = lock(&self.state);
+        let op = match &*state {
+            OperationState::Pending(op) => op.0.as_ptr(),
+            _ => return Err(CANCELLED),
+        };
+        let result = unsafe { s2n_async_offload_op_perform(op).into_result() }.map(|_| ());
+        *state = OperationState::Performed {
+            success: result.is_ok(),
+        };
+        result
+    }
+}
+
+/// A trait for the callback used to offload expensive operations,
+/// like signature verification, during the handshake.
+///
+/// Use in conjunction with
+/// [config::Builder::set_async_offload_callback](`crate::config::Builder::set_async_offload_callback()`).
+pub trait AsyncOffloadCallback: 'static + Send + Sync {
+    /// The application can return an `Ok(None)` to perform the operation
+    /// synchronously, before the handshake continues.
+    ///
+    /// The application can return an `Ok(Some(ConnectionFuture))` to perform
+    /// the operation asynchronously, for example on a blocking thread pool.
+    /// The handshake resumes once the future completes. If the future completes
+    /// without calling [`AsyncOffloadOperation::perform()`], the operation is
+    /// performed synchronously instead.
+    ///
+    /// The application can return an `Err(Error)` to fail the handshake.
+    fn handle_operation(
+        &self,
+        connection: &mut Connection,
+        operation: AsyncOffloadOperation,
+    ) -> ConnectionFutureResult;
+}
+
+/// Completes an offloaded operation once the application's future completes.
+struct OffloadFuture {
+    future: Option<Pin<Box<dyn ConnectionFuture>>>,
+    state: SharedState,
+}
+
+impl OffloadFuture {
+    /// Performs the operation if the application has not already.
+    fn finish(&self) -> Result<(), Error> {
+        let state = core::mem::replace(&mut *lock(&self.state), OperationState::Cancelled);
+        match state {
+            OperationState::Pending(op) => AsyncOffloadOperation::new(op).perform(),
+            OperationState::Performed { success: true } => Ok(()),
+            OperationState::Performed { success: false } => Err(FAILED),
+            OperationState::Cancelled => Err(CANCELLED),
+        }
+    }
+}
+
+impl ConnectionFuture for OffloadFuture {
+    fn poll(
+        mut self: Pin<&mut Self>,
+        conn: &mut Connection,
+        ctx: &mut core::task::Context,
+    ) -> Poll<Result<(), Error>> {
+        if let Some(future) = self.future.as_mut() {
+            match future.as_mut().poll(conn, ctx) {
+                Poll::Ready(Ok(())) => self.future = None,
+                other => return other,
+            }
+        }
+        Poll::Ready(self.finish())
+    }
+}
+
+impl Drop for OffloadFuture {
+    fn drop(&mut self) {
+        // The operation is owned by the connection, which drops this future
+        // before the operation is freed. Waiting for the lock ensures that
+        // no other thread is still performing the operation.
+        *lock(&self.state) = OperationState::Cancelled;
+    }
+}
+
+pub(crate) unsafe extern "C" fn async_offload_cb(
+    conn_ptr: *mut s2n_connection,
+    op_ptr: *mut s2n_async_offload_op,
+    _context: *mut core::ffi::c_void,
+) -> libc::c_int {
+    with_context(conn_ptr, |conn, context| {
+        let op = match NonNull::new(op_ptr) {
+            Some(op) => OperationPtr(op),
+            None => return CallbackResult::Failure,
+        };
+        let operation = AsyncOffloadOperation::new(op);
+        let state = operation.state.clone();
+
+        let callback = context.async_offload_callback.as_ref();
+        let future_result = callback.map_or(Ok(None), |callback| {
+            callback.handle_operation(conn, operation)
+        });
+        let future = match future_result {
+            Ok(future) => OffloadFuture { future, state },
+            Err(error) => {
+                *lock(&state) = OperationState::Cancelled;
+                return AsyncCallback::trigger(Err(error), conn);
+            }
+        };
+
+        if future.future.is_some() {
+            return AsyncCallback::trigger(Ok(Some(Box::pin(future))), conn);
+        }
+        // Without a future, there is no reason to block the handshake.
+        match future.finish() {
+            Ok(()) => CallbackResult::Success,
+            // Report the error when the blocked handshake is next polled
+            Err(error) => AsyncCallback::trigger(Err(error), conn),
+        }
+    })
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        security,
+        testing::{self, Counter, TestPair},
+    };
+    use futures_test::task::noop_waker;
+    use std::thread::JoinHandle;
+
+    type Result<T, E = Box<dyn std::error::Error>> = core::result::Result<T, E>;
+
+    fn new_pair<T: AsyncOffloadCallback>(callback: T) -> Result<TestPair> {
+        let mut client = testing::config_builder(&security::DEFAULT_TLS13)?;
+        client.set_async_offload_callback(callback, &[OffloadOperationType::PkeyVerify])?;
+        let server = testing::build_config(&security::DEFAULT_TLS13)?;
+
+        let mut pair = TestPair::from_configs(&client.build()?, &server);
+        pair.client.set_waker(Some(&noop_waker()))?;
+        Ok(pair)
+    }
+
+    /// Performs the operation on another thread.
+    struct ThreadFuture(Option<JoinHandle<Result<(), Error>>>);
+
+    impl ConnectionFuture for ThreadFuture {
+        fn poll(
+            mut self: Pin<&mut Self>,
+            _conn: &mut Connection,
+            ctx: &mut core::task::Context,
+        ) -> Poll<Result<(), Error>> {
+            let handle = self.0.take().expect("polled after completion");
+            if handle.is_finished() {
+                Poll::Ready(handle.join().expect("offload thread panicked"))
+            } else {
+                self.0 = Some(handle);
+                ctx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    enum Mode {
+        Sync,
+        Thread,
+        Skip,
+        Fail,
+    }
+
+    struct TestCallback(Mode, Counter);
+
+    impl AsyncOffloadCallback for TestCallback {
+        fn handle_operation(
+            &self,
+            _conn: &mut Connection,
+            op: AsyncOffloadOperation,
+        ) -> ConnectionFutureResult {
+            self.1.increment();
+            match self.0 {
+                Mode::Sync => {
+                    op.perform()?;
+                    Ok(None)
+                }
+                Mode::Thread => {
+                    let handle = std::thread::spawn(move || op.perform());
+                    Ok(Some(Box::pin(ThreadFuture(Some(handle)))))
+                }
+                Mode::Skip => Ok(None),
+                Mode::Fail => Err(Error::application("offload failed".into())),
+            }
+        }
+    }
+
+    fn handshake(mode: Mode) -> Result<(TestPair, Counter)> {
+        let counter = Counter::default();
+        let mut pair = new_pair(TestCallback(mode, counter.clone()))?;
+        pair.handshake()?;
+        Ok((pair, counter))
+    }
+
+    #[test]
+    fn sync_perform() -> Result<()> {
+        let (_, counter) = handshake(Mode::Sync)?;
+        assert_eq!(counter.count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn thread_perform() -> Result<()> {
+        let (_, counter) = handshake(Mode::Thread)?;
+        assert_eq!(counter.count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn perform_skipped() -> Result<()> {
+        // The operation is still performed if the application doesn't
+        let (_, counter) = handshake(Mode::Skip)?;
+        assert_eq!(counter.count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn callback_error() -> Result<()> {
+        let counter = Counter::default();
+        let mut pair = new_pair(TestCallback(Mode::Fail, counter.clone()))?;
+        let err = pair.handshake().unwrap_err();
+        assert_eq!(err.kind(), ErrorType::Application);
+        assert_eq!(counter.count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn not_allowed() -> Result<()> {
+        let counter = Counter::default();
+        let mut client = testing::config_builder(&security::DEFAULT_TLS13)?;
+        client.set_async_offload_callback(TestCallback(Mode::Sync, counter.clone()), &[])?;
+        let server = testing::build_config(&security::DEFAULT_TLS13)?;
+
+        let mut pair = TestPair::from_configs(&client.build()?, &server);
+        pair.handshake()?;
+        assert_eq!(counter.count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn perform_after_drop() -> Result<()> {
+        /// Never completes.
+        struct PendingFuture;
+        impl ConnectionFuture for PendingFuture {
+            fn poll(
+                self: Pin<&mut Self>,
+                _conn: &mut Connection,
+                _ctx: &mut core::task::Context,
+            ) -> Poll<Result<(), Error>> {
+                Poll::Pending
+            }
+        }
+
+        type Slot = Arc<Mutex<Option<AsyncOffloadOperation>>>;
+        struct KeepCallback(Slot);
+        impl AsyncOffloadCallback for KeepCallback {
+            fn handle_operation(
+                &self,
+                _conn: &mut Connection,
+                op: AsyncOffloadOperation,
+            ) -> ConnectionFutureResult {
+                *self.0.lock().unwrap() = Some(op);
+                Ok(Some(Box::pin(PendingFuture)))
+            }
+        }
+
+        let slot = Slot::default();
+        let mut pair = new_pair(KeepCallback(slot.clone()))?;
+        for _ in 0..10 {
+            assert!(pair.client.poll_negotiate().is_pending());
+            assert!(pair.server.poll_negotiate().is_pending());
+        }
+        drop(pair);
+
+        let op = slot.lock().unwrap().take().expect("callback not called");
+        assert_eq!(op.perform().unwrap_err().name(), "AsyncOffloadError");
+        Ok(())
+    }
+}
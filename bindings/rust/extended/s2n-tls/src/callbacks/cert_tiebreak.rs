@@ -0,0 +1,182 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for resolving conflicts between certificate chains for the same domain name.
+
+use crate::cert_chain::CertificateChain;
+use s2n_tls_sys::*;
+use std::{cell::Cell, ptr::NonNull};
+
+/// A trait for the callback used to choose between certificate chains
+/// with the same domain name.
+///
+/// s2n-tls selects certificates by domain name and key type, so the callback
+/// is only called when a chain is loaded with the same name and key type
+/// (RSA, ECDSA, etc.) as an existing chain. Chains with different key types
+/// are both kept, and chosen between during the handshake based on the
+/// signature algorithms supported by the client.
+///
+/// Use in conjunction with
+/// [config::Builder::set_cert_tiebreak_callback](`crate::config::Builder::set_cert_tiebreak_callback()`).
+pub trait CertTiebreakCallback: 'static + Send + Sync {
+    /// Returns the index of the chain in `candidates` to use for `name`.
+    ///
+    /// `candidates` contains the chain currently used for `name`, followed by
+    /// the chain being loaded. Returning `None` keeps the current chain.
+    ///
+    /// Application-specific information can be attached to each chain with
+    /// [cert_chain::Builder::set_application_context](`crate::cert_chain::Builder::set_application_context()`).
+    fn choose_chain(&self, name: &str, candidates: &[CertificateChain]) -> Option<usize>;
+}
+
+thread_local! {
+    /// The callback for the config currently loading a certificate chain.
+    ///
+    /// s2n_cert_tiebreak_callback has no context pointer, but is only called
+    /// synchronously while a chain is added to a config.
+    static ACTIVE_CALLBACK: Cell<Option<*const dyn CertTiebreakCallback>> = Cell::new(None);
+}
+
+/// Makes `callback` available to [`cert_tiebreak_cb`] while `f` runs.
+pub(crate) fn with_tiebreak_callback<T>(
+    callback: Option<&dyn CertTiebreakCallback>,
+    f: impl FnOnce() -> T,
+) -> T {
+    /// Restores the previous callback, even if `f` panics.
+    struct Reset(Option<*const dyn CertTiebreakCallback>);
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            ACTIVE_CALLBACK.with(|active| active.set(self.0));
+        }
+    }
+
+    let callback = callback.map(|callback| callback as *const dyn CertTiebreakCallback);
+    let _reset = Reset(ACTIVE_CALLBACK.with(|active| active.replace(callback)));
+    f()
+}
+
+pub(crate) unsafe extern "C" fn cert_tiebreak_cb(
+    cert1: *mut s2n_cert_chain_and_key,
+    cert2: *mut s2n_cert_chain_and_key,
+    name: *mut u8,
+    name_len: u32,
+) -> *mut s2n_cert_chain_and_key {
+    let callback = match ACTIVE_CALLBACK.with(Cell::get) {
+        // SAFETY: the callback is owned by the config being built, and is only
+        // set for the duration of the call that added the chain to it.
+        Some(callback) => &*callback,
+        None => return core::ptr::null_mut(),
+    };
+    let (chain1, chain2) = match (NonNull::new(cert1), NonNull::new(cert2)) {
+        (Some(chain1), Some(chain2)) => (chain1, chain2),
+        _ => return core::ptr::null_mut(),
+    };
+    if name.is_null() {
+        return core::ptr::null_mut();
+    }
+    let name = core::slice::from_raw_parts(name, name_len as usize);
+    let name = match core::str::from_utf8(name) {
+        Ok(name) => name,
+        Err(_) => return core::ptr::null_mut(),
+    };
+
+    // SAFETY: both chains are owned by the config, which outlives the callback.
+    let candidates = [
+        CertificateChain::from_ptr_reference(chain1),
+        CertificateChain::from_ptr_reference(chain2),
+    ];
+    match callback.choose_chain(name, &candidates) {
+        Some(0) => cert1,
+        Some(1) => cert2,
+        _ => core::ptr::null_mut(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cert_chain, config,
+        error::Error,
+        security::DEFAULT_TLS13,
+        testing::{InsecureAcceptAllCertificatesHandler, SniTestCerts, TestPair},
+    };
+
+    const NAME: &str = "www.alligator.com";
+
+    /// The version of a chain, attached as its application context.
+    struct Version(u32);
+
+    /// Prefers the chain with the highest version.
+    struct NewestChain;
+
+    impl CertTiebreakCallback for NewestChain {
+        fn choose_chain(&self, name: &str, candidates: &[CertificateChain]) -> Option<usize> {
+            assert_eq!(name, NAME);
+            (0..candidates.len()).max_by_key(|i| {
+                candidates[*i]
+                    .application_context::<Version>()
+                    .map(|version| version.0)
+            })
+        }
+    }
+
+    fn versioned_chain(version: u32) -> Result<CertificateChain<'static>, Error> {
+        let keypair = SniTestCerts::AlligatorRsa.get();
+        let mut chain = cert_chain::Builder::new()?;
+        chain
+            .load_pem(keypair.cert(), keypair.key())?
+            .set_application_context(Version(version))?;
+        chain.build()
+    }
+
+    /// Returns the version of the chain the server selects for NAME.
+    fn selected_version(mut server: config::Builder) -> Result<Option<u32>, Error> {
+        server.set_security_policy(&DEFAULT_TLS13)?;
+
+        let mut client = config::Builder::new();
+        client
+            .set_security_policy(&DEFAULT_TLS13)?
+            .trust_pem(SniTestCerts::AlligatorRsa.get().cert())?
+            .set_verify_host_callback(InsecureAcceptAllCertificatesHandler {})?;
+
+        let mut pair = TestPair::from_configs(&client.build()?, &server.build()?);
+        pair.client.set_server_name(NAME)?;
+        pair.handshake()?;
+
+        let selected = pair.server.selected_cert().unwrap();
+        Ok(selected.application_context::<Version>().map(|v| v.0))
+    }
+
+    #[test]
+    fn tiebreak() -> Result<(), Error> {
+        let mut server = config::Builder::new();
+        server
+            .set_cert_tiebreak_callback(NewestChain)?
+            .load_chain(versioned_chain(1)?)?
+            .load_chain(versioned_chain(3)?)?
+            .load_chain(versioned_chain(2)?)?;
+        assert_eq!(selected_version(server)?, Some(3));
+        Ok(())
+    }
+
+    #[test]
+    fn no_tiebreak() -> Result<(), Error> {
+        // Without the callback, the first chain is used
+        let mut server = config::Builder::new();
+        server
+            .load_chain(versioned_chain(1)?)?
+            .load_chain(versioned_chain(3)?)?;
+        assert_eq!(selected_version(server)?, Some(1));
+        Ok(())
+    }
+
+    #[test]
+    fn callback_scope() {
+        assert!(ACTIVE_CALLBACK.with(Cell::get).is_none());
+        with_tiebreak_callback(Some(&NewestChain), || {
+            assert!(ACTIVE_CALLBACK.with(Cell::get).is_some());
+        });
+        assert!(ACTIVE_CALLBACK.with(Cell::get).is_none());
+    }
+}
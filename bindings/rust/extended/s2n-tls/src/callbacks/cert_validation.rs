@@ -4,7 +4,9 @@
 use s2n_tls_sys::*;
 
 use crate::{
+    callbacks::with_context,
     connection::Connection,
+    enums::CallbackResult,
     error::{Error, Fallible},
 };
 use std::{marker::PhantomData, ptr::NonNull};
@@ -49,6 +51,32 @@ pub trait CertValidationCallbackSync: 'static + Send + Sync {
     ) -> Result<bool, Error>;
 }
 
+/// The cert validation callback installed by both
+/// [config::Builder::set_cert_validation_callback_sync](`crate::config::Builder::set_cert_validation_callback_sync()`)
+/// and [config::Builder::set_ocsp_must_staple](`crate::config::Builder::set_ocsp_must_staple()`).
+///
+/// Certificates that are missing a required OCSP staple are rejected before
+/// the application's callback is consulted.
+pub(crate) unsafe extern "C" fn cert_validation_cb(
+    conn_ptr: *mut s2n_connection,
+    validation_info: *mut s2n_cert_validation_info,
+    _context: *mut core::ffi::c_void,
+) -> libc::c_int {
+    let mut info = CertValidationInfo::from_ptr(validation_info);
+    with_context(conn_ptr, |conn, context| {
+        let accepted = conn.check_must_staple().is_ok()
+            && match context.cert_validation_callback_sync.as_ref() {
+                Some(callback) => callback.handle_validation(conn, &mut info).unwrap(),
+                None => true,
+            };
+        match accepted {
+            true => info.accept().unwrap(),
+            false => info.reject().unwrap(),
+        }
+    });
+    CallbackResult::Success.into()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
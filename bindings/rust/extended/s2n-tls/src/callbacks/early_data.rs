@@ -0,0 +1,316 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for application-implemented early data callbacks.
+
+use crate::{
+    callbacks::*,
+    connection::Connection,
+    error::{Error, Fallible},
+    ffi::*,
+};
+use std::ptr::NonNull;
+
+/// Corresponds to [s2n_offered_early_data].
+///
+/// Early data offered by the client, which the server must either accept or reject.
+/// If neither [`Self::accept()`] nor [`Self::reject()`] is called, the handshake
+/// will not complete.
+pub struct OfferedEarlyData {
+    raw: NonNull<s2n_offered_early_data>,
+}
+
+/// # Safety
+///
+/// Safety: s2n_offered_early_data objects can be sent across threads
+unsafe impl Send for OfferedEarlyData {}
+
+/// # Safety
+///
+/// Safety: All C methods that mutate the s2n_offered_early_data are wrapped
+/// in Rust methods that require ownership.
+unsafe impl Sync for OfferedEarlyData {}
+
+impl OfferedEarlyData {
+    pub(crate) fn from_cb(early_data: *mut s2n_offered_early_data) -> Result<Self, Error> {
+        let raw = NonNull::new(early_data).ok_or(Error::INVALID_INPUT)?;
+        Ok(OfferedEarlyData { raw })
+    }
+
+    /// The early data context associated with the offered PSK or session ticket.
+    ///
+    /// See [psk::Builder::set_early_data_context](`crate::psk::Builder::set_early_data_context()`)
+    /// and [Connection::set_server_early_data_context](`crate::connection::Connection::set_server_early_data_context()`).
+    ///
+    /// Corresponds to [s2n_offered_early_data_get_context], but also calls
+    /// [s2n_offered_early_data_get_context_length].
+    pub fn context(&self) -> Result<Vec<u8>, Error> {
+        let mut len = 0;
+        unsafe {
+            s2n_offered_early_data_get_context_length(self.as_ptr(), &mut len).into_result()
+        }?;
+        let mut context = vec![0; len as usize];
+        unsafe {
+            s2n_offered_early_data_get_context(self.as_ptr(), context.as_mut_ptr(), len)
+                .into_result()
+        }?;
+        Ok(context)
+    }
+
+    /// Accept the early data offered by the client.
+    ///
+    /// Corresponds to [s2n_offered_early_data_accept].
+    pub fn accept(self) -> Result<(), Error> {
+        unsafe { s2n_offered_early_data_accept(self.as_ptr()).into_result() }?;
+        Ok(())
+    }
+
+    /// Reject the early data offered by the client.
+    ///
+    /// Corresponds to [s2n_offered_early_data_reject].
+    pub fn reject(self) -> Result<(), Error> {
+        unsafe { s2n_offered_early_data_reject(self.as_ptr()).into_result() }?;
+        Ok(())
+    }
+
+    fn as_ptr(&self) -> *mut s2n_offered_early_data {
+        self.raw.as_ptr()
+    }
+}
+
+/// A trait for the callback used to accept or reject early data.
+///
+/// The callback is only triggered if s2n-tls has already determined that
+/// the early data is otherwise acceptable, so implementations only need to
+/// apply application-specific checks, like anti-replay protection.
+///
+/// Use in conjunction with
+/// [config::Builder::set_early_data_callback](`crate::config::Builder::set_early_data_callback()`).
+pub trait EarlyDataCallback: 'static + Send + Sync {
+    /// The application can accept or reject `early_data` and return `Ok(None)`
+    /// to resolve the callback synchronously, or return an
+    /// `Ok(Some(ConnectionFuture))` which accepts or rejects `early_data` later.
+    fn on_early_data(
+        &self,
+        connection: &mut Connection,
+        early_data: OfferedEarlyData,
+    ) -> ConnectionFutureResult;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::Config,
+        enums::{EarlyDataStatus, PskHmac},
+        psk::Psk,
+        security,
+        testing::*,
+    };
+    use core::task::{Poll, Waker};
+    use futures_test::task::{new_count_waker, noop_waker};
+    use std::pin::Pin;
+
+    const EARLY_DATA: &[u8] = b"hello from the past";
+    const MAX_EARLY_DATA: u32 = 1024;
+    const CONTEXT: &[u8] = b"early data context";
+    // TLS_AES_128_GCM_SHA256
+    const CIPHER_SUITE: [u8; 2] = [0x13, 0x01];
+
+    fn early_data_psk() -> Result<Psk, Error> {
+        let mut builder = Psk::builder()?;
+        builder.set_identity(b"early data identity")?;
+        builder.set_secret(b"a secret that is at least 128 bits long")?;
+        builder.set_hmac(PskHmac::SHA256)?;
+        builder.set_early_data(MAX_EARLY_DATA, CIPHER_SUITE)?;
+        builder.set_early_data_context(CONTEXT)?;
+        builder.build()
+    }
+
+    fn early_data_pair<T: EarlyDataCallback>(
+        callback: T,
+        waker: &Waker,
+    ) -> Result<TestPair, Error> {
+        let client_config = {
+            let mut config = Config::builder();
+            config.set_security_policy(&security::DEFAULT_TLS13)?;
+            config.build()?
+        };
+        let server_config = {
+            let mut config = Config::builder();
+            config.set_security_policy(&security::DEFAULT_TLS13)?;
+            config.set_early_data_callback(callback)?;
+            config.build()?
+        };
+
+        let psk = early_data_psk()?;
+        let mut pair = TestPair::from_configs(&client_config, &server_config);
+        pair.client.append_psk(&psk)?;
+        pair.server.append_psk(&psk)?;
+        pair.server.set_waker(Some(waker))?;
+        Ok(pair)
+    }
+
+    /// Send early data from the client and receive it on the server.
+    ///
+    /// The server can't finish receiving early data until the client sends
+    /// the EndOfEarlyData message, so the client negotiates once it is done
+    /// sending early data.
+    fn exchange_early_data(pair: &mut TestPair) -> Result<Vec<u8>, Error> {
+        let mut sent = 0;
+        let mut client_done = false;
+        let mut received = vec![0; MAX_EARLY_DATA as usize];
+        let mut received_len = 0;
+        let mut server_done = false;
+
+        while !server_done {
+            if client_done {
+                let _ = pair.client.poll_negotiate()?;
+            } else if let Poll::Ready(n) = pair.client.poll_send_early_data(&EARLY_DATA[sent..])? {
+                sent += n;
+                client_done = n == 0 || sent == EARLY_DATA.len();
+            }
+
+            if let Poll::Ready(n) = pair
+                .server
+                .poll_recv_early_data(&mut received[received_len..])?
+            {
+                received_len += n;
+                server_done = n == 0;
+            }
+        }
+
+        received.truncate(received_len);
+        Ok(received)
+    }
+
+    #[test]
+    fn sync_accept() -> Result<(), Error> {
+        struct AcceptCallback(Counter);
+        impl EarlyDataCallback for AcceptCallback {
+            fn on_early_data(
+                &self,
+                _conn: &mut Connection,
+                early_data: OfferedEarlyData,
+            ) -> ConnectionFutureResult {
+                self.0.increment();
+                assert_eq!(early_data.context()?, CONTEXT);
+                early_data.accept()?;
+                Ok(None)
+            }
+        }
+
+        let counter = Counter::default();
+        let mut pair = early_data_pair(AcceptCallback(counter.clone()), &noop_waker())?;
+
+        let received = exchange_early_data(&mut pair)?;
+        pair.handshake()?;
+
+        assert_eq!(counter.count(), 1);
+        assert_eq!(received, EARLY_DATA);
+        for conn in [&pair.client, &pair.server] {
+            assert_eq!(conn.early_data_status()?, EarlyDataStatus::End);
+            assert_eq!(conn.max_early_data_size()?, MAX_EARLY_DATA as usize);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn sync_reject() -> Result<(), Error> {
+        struct RejectCallback;
+        impl EarlyDataCallback for RejectCallback {
+            fn on_early_data(
+                &self,
+                _conn: &mut Connection,
+                early_data: OfferedEarlyData,
+            ) -> ConnectionFutureResult {
+                early_data.reject()?;
+                Ok(None)
+            }
+        }
+
+        let mut pair = early_data_pair(RejectCallback, &noop_waker())?;
+
+        let received = exchange_early_data(&mut pair)?;
+        pair.handshake()?;
+
+        assert!(received.is_empty());
+        for conn in [&pair.client, &pair.server] {
+            assert_eq!(conn.early_data_status()?, EarlyDataStatus::Rejected);
+            assert_eq!(conn.remaining_early_data_size()?, 0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn async_accept() -> Result<(), Error> {
+        const POLL_COUNT: usize = 10;
+
+        struct AcceptFuture {
+            counter: usize,
+            early_data: Option<OfferedEarlyData>,
+        }
+        impl ConnectionFuture for AcceptFuture {
+            fn poll(
+                mut self: Pin<&mut Self>,
+                _conn: &mut Connection,
+                ctx: &mut core::task::Context,
+            ) -> Poll<Result<(), Error>> {
+                ctx.waker().wake_by_ref();
+                self.counter += 1;
+                if self.counter < POLL_COUNT {
+                    return Poll::Pending;
+                }
+                let early_data = self.early_data.take().expect("polled after completion");
+                Poll::Ready(early_data.accept())
+            }
+        }
+
+        struct AsyncAcceptCallback;
+        impl EarlyDataCallback for AsyncAcceptCallback {
+            fn on_early_data(
+                &self,
+                _conn: &mut Connection,
+                early_data: OfferedEarlyData,
+            ) -> ConnectionFutureResult {
+                let future = AcceptFuture {
+                    counter: 0,
+                    early_data: Some(early_data),
+                };
+                Ok(Some(Box::pin(future)))
+            }
+        }
+
+        let (waker, wake_count) = new_count_waker();
+        let mut pair = early_data_pair(AsyncAcceptCallback, &waker)?;
+
+        let received = exchange_early_data(&mut pair)?;
+        pair.handshake()?;
+
+        assert_eq!(wake_count, POLL_COUNT);
+        assert_eq!(received, EARLY_DATA);
+        assert_eq!(pair.server.early_data_status()?, EarlyDataStatus::End);
+        Ok(())
+    }
+
+    #[test]
+    fn callback_error() -> Result<(), Error> {
+        const ERROR: &str = "early data callback error";
+
+        struct ErrorCallback;
+        impl EarlyDataCallback for ErrorCallback {
+            fn on_early_data(
+                &self,
+                _conn: &mut Connection,
+                _early_data: OfferedEarlyData,
+            ) -> ConnectionFutureResult {
+                Err(test_error(ERROR))
+            }
+        }
+
+        let mut pair = early_data_pair(ErrorCallback, &noop_waker())?;
+        let err = exchange_early_data(&mut pair).unwrap_err();
+        assert_test_error(err, ERROR);
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for application-implemented PSK selection.
+
+use crate::{
+    connection::Connection,
+    error::{Error, Fallible},
+};
+use s2n_tls_sys::*;
+use std::{marker::PhantomData, ptr::NonNull};
+
+/// A PSK offered by the client.
+///
+/// Corresponds to [s2n_offered_psk].
+pub struct OfferedPsk<'a> {
+    raw: NonNull<s2n_offered_psk>,
+    identity: &'a [u8],
+}
+
+impl<'a> OfferedPsk<'a> {
+    /// Corresponds to [s2n_offered_psk_new] and [s2n_offered_psk_list_next].
+    fn next_from(list: &mut OfferedPskList<'a>) -> Result<Self, Error> {
+        let raw = unsafe { s2n_offered_psk_new().into_result() }?;
+        // Construct before reading so that the offered psk is freed on failure.
+        let mut psk = OfferedPsk { raw, identity: &[] };
+        unsafe { s2n_offered_psk_list_next(list.raw.as_ptr(), psk.raw.as_ptr()).into_result() }?;
+
+        let mut identity = core::ptr::null_mut();
+        let mut size = 0;
+        unsafe {
+            s2n_offered_psk_get_identity(psk.raw.as_ptr(), &mut identity, &mut size).into_result()
+        }?;
+        psk.identity = if size == 0 {
+            &[]
+        } else {
+            // SAFETY: the identity points into the offered psk list, which is
+            // valid for the lifetime of the list.
+            unsafe { core::slice::from_raw_parts(identity, size.into()) }
+        };
+        Ok(psk)
+    }
+
+    /// The public identity of the PSK.
+    ///
+    /// Corresponds to [s2n_offered_psk_get_identity].
+    pub fn identity(&self) -> &'a [u8] {
+        self.identity
+    }
+}
+
+impl Drop for OfferedPsk<'_> {
+    /// Corresponds to [s2n_offered_psk_free].
+    fn drop(&mut self) {
+        // ignore failures since there's not much we can do about it
+        let _ = unsafe { s2n_offered_psk_free(&mut self.raw.as_ptr()).into_result() };
+    }
+}
+
+/// The list of PSKs offered by the client.
+///
+/// Iterating over the list returns each [`OfferedPsk`] in the order the client
+/// offered them.
+///
+/// Corresponds to [s2n_offered_psk_list].
+pub struct OfferedPskList<'a> {
+    raw: NonNull<s2n_offered_psk_list>,
+    _lifetime: PhantomData<&'a s2n_offered_psk_list>,
+}
+
+impl OfferedPskList<'_> {
+    pub(crate) fn from_cb(list: *mut s2n_offered_psk_list) -> Result<Self, Error> {
+        let raw = NonNull::new(list).ok_or(Error::INVALID_INPUT)?;
+        Ok(OfferedPskList {
+            raw,
+            _lifetime: PhantomData,
+        })
+    }
+
+    /// Restart iteration from the first offered PSK.
+    ///
+    /// Corresponds to [s2n_offered_psk_list_reread].
+    pub fn reread(&mut self) -> Result<&mut Self, Error> {
+        unsafe { s2n_offered_psk_list_reread(self.raw.as_ptr()).into_result() }?;
+        Ok(self)
+    }
+
+    /// Choose the PSK to use for the connection.
+    ///
+    /// The server must already know a PSK with the same identity, added with
+    /// [Connection::append_psk](`crate::connection::Connection::append_psk()`).
+    ///
+    /// Corresponds to [s2n_offered_psk_list_choose_psk].
+    pub fn choose(&mut self, psk: &OfferedPsk) -> Result<&mut Self, Error> {
+        unsafe {
+            s2n_offered_psk_list_choose_psk(self.raw.as_ptr(), psk.raw.as_ptr()).into_result()
+        }?;
+        Ok(self)
+    }
+}
+
+impl<'a> Iterator for OfferedPskList<'a> {
+    type Item = Result<OfferedPsk<'a>, Error>;
+
+    /// Corresponds to [s2n_offered_psk_list_has_next] and [s2n_offered_psk_list_next].
+    fn next(&mut self) -> Option<Self::Item> {
+        if unsafe { s2n_offered_psk_list_has_next(self.raw.as_ptr()) } {
+            Some(OfferedPsk::next_from(self))
+        } else {
+            None
+        }
+    }
+}
+
+/// A trait for the callback used by a server to choose which PSK to use.
+///
+/// If the callback fails or does not choose a PSK, then the connection
+/// proceeds without a PSK.
+///
+/// Use in conjunction with
+/// [config::Builder::set_psk_selection_callback](`crate::config::Builder::set_psk_selection_callback()`).
+pub trait PskSelectionCallback: 'static + Send + Sync {
+    /// Call [`OfferedPskList::choose()`] with one of the offered PSKs.
+    ///
+    /// PSKs can be added to the connection from an application-managed store
+    /// with [Connection::append_psk](`crate::connection::Connection::append_psk()`)
+    /// before being chosen.
+    fn select_psk(
+        &self,
+        connection: &mut Connection,
+        psk_list: &mut OfferedPskList,
+    ) -> Result<(), Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{enums::PskHmac, psk::Psk, security, testing::*};
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    fn test_psk(identity: &[u8]) -> Result<Psk, Error> {
+        let mut builder = Psk::builder()?;
+        builder.set_identity(identity)?;
+        builder.set_secret(b"a secret that is at least 128 bits long")?;
+        builder.set_hmac(PskHmac::SHA256)?;
+        builder.build()
+    }
+
+    /// Chooses the first offered PSK found in the store.
+    struct StoreCallback {
+        store: HashMap<Vec<u8>, Psk>,
+        offered: Mutex<Vec<Vec<u8>>>,
+    }
+
+    impl StoreCallback {
+        fn new(identities: &[&[u8]]) -> Result<Self, Error> {
+            let mut store = HashMap::new();
+            for identity in identities {
+                store.insert(identity.to_vec(), test_psk(identity)?);
+            }
+            let offered = Mutex::default();
+            Ok(Self { store, offered })
+        }
+    }
+
+    impl PskSelectionCallback for StoreCallback {
+        fn select_psk(
+            &self,
+            conn: &mut Connection,
+            psk_list: &mut OfferedPskList,
+        ) -> Result<(), Error> {
+            let mut offered = self.offered.lock().unwrap();
+            for psk in psk_list.by_ref() {
+                offered.push(psk?.identity().to_vec());
+            }
+
+            psk_list.reread()?;
+            while let Some(psk) = psk_list.next() {
+                let psk = psk?;
+                if let Some(known) = self.store.get(psk.identity()) {
+                    conn.append_psk(known)?;
+                    psk_list.choose(&psk)?;
+                    break;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn negotiated_identity(conn: &Connection) -> Result<Vec<u8>, Error> {
+        let mut identity = vec![0; conn.negotiated_psk_identity_length()?];
+        conn.negotiated_psk_identity(&mut identity)?;
+        Ok(identity)
+    }
+
+    fn psk_selection_pair(
+        server_identities: &[&[u8]],
+        client_identities: &[&[u8]],
+    ) -> Result<(TestPair, Arc<StoreCallback>), Error> {
+        struct SharedCallback(Arc<StoreCallback>);
+        impl PskSelectionCallback for SharedCallback {
+            fn select_psk(
+                &self,
+                conn: &mut Connection,
+                psk_list: &mut OfferedPskList,
+            ) -> Result<(), Error> {
+                self.0.select_psk(conn, psk_list)
+            }
+        }
+
+        let callback = Arc::new(StoreCallback::new(server_identities)?);
+        let server_config = {
+            let mut config = config_builder(&security::DEFAULT_TLS13)?;
+            config.set_psk_selection_callback(SharedCallback(callback.clone()))?;
+            config.build()?
+        };
+        let client_config = build_config(&security::DEFAULT_TLS13)?;
+
+        let mut pair = TestPair::from_configs(&client_config, &server_config);
+        for identity in client_identities {
+            pair.client.append_psk(&test_psk(identity)?)?;
+        }
+        Ok((pair, callback))
+    }
+
+    #[test]
+    fn select_from_store() -> Result<(), Error> {
+        let (mut pair, callback) =
+            psk_selection_pair(&[b"carol", b"bob"], &[b"alice", b"bob", b"carol"])?;
+        pair.handshake()?;
+
+        let offered = callback.offered.lock().unwrap();
+        assert_eq!(
+            *offered,
+            vec![b"alice".to_vec(), b"bob".to_vec(), b"carol".to_vec()]
+        );
+        for conn in [&pair.client, &pair.server] {
+            assert_eq!(negotiated_identity(conn)?, b"bob");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn no_psk_chosen() -> Result<(), Error> {
+        let (mut pair, callback) = psk_selection_pair(&[b"carol"], &[b"alice", b"bob"])?;
+        // Without a PSK, the handshake falls back to certificate authentication
+        pair.handshake()?;
+
+        assert_eq!(callback.offered.lock().unwrap().len(), 2);
+        for conn in [&pair.client, &pair.server] {
+            assert_eq!(conn.negotiated_psk_identity_length()?, 0);
+        }
+        Ok(())
+    }
+}
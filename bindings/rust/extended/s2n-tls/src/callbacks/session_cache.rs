@@ -0,0 +1,371 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for server-side session caching, used to resume TLS1.2 sessions
+//! by session ID.
+
+use crate::{connection::Connection, error::Error};
+use core::task::Poll;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// A trait for the cache a server uses to store sessions for resumption.
+///
+/// Caching is only used for TLS1.2 sessions. TLS1.3 sessions are always resumed
+/// with session tickets. Entries are encrypted by s2n-tls with the configured
+/// session ticket keys before being stored, so at least one key must be added with
+/// [config::Builder::add_session_ticket_key](`crate::config::Builder::add_session_ticket_key()`).
+///
+/// Errors do not fail the handshake: if an entry can't be stored or
+/// retrieved, the connection falls back to a full handshake.
+///
+/// Use in conjunction with
+/// [config::Builder::set_session_cache](`crate::config::Builder::set_session_cache()`).
+pub trait SessionCache: 'static + Send + Sync {
+    /// Store `value` under `key` for at most `ttl`.
+    fn store(
+        &self,
+        connection: &mut Connection,
+        ttl: Duration,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<(), Error>;
+
+    /// Retrieve the value stored under `key`, or `None` if there is no entry.
+    ///
+    /// If the entry isn't available yet, return [Poll::Pending] and wake the
+    /// connection's [waker](`crate::connection::Connection::waker()`) once it is.
+    /// The method will be called again with the same `key` when the handshake is polled.
+    fn retrieve(
+        &self,
+        connection: &mut Connection,
+        key: &[u8],
+    ) -> Poll<Result<Option<Vec<u8>>, Error>>;
+
+    /// Remove the entry stored under `key`.
+    ///
+    /// Called if a connection using the entry fails, so that it isn't resumed.
+    fn delete(&self, connection: &mut Connection, key: &[u8]) -> Result<(), Error>;
+}
+
+struct CacheEntry {
+    value: Vec<u8>,
+    expiration: Instant,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct LruEntries {
+    entries: HashMap<Vec<u8>, CacheEntry>,
+    /// Keys ordered from least to most recently used.
+    usage: BTreeMap<u64, Vec<u8>>,
+    counter: u64,
+}
+
+impl LruEntries {
+    fn next_use(&mut self) -> u64 {
+        self.counter += 1;
+        self.counter
+    }
+
+    fn remove(&mut self, key: &[u8]) -> Option<CacheEntry> {
+        let entry = self.entries.remove(key)?;
+        self.usage.remove(&entry.last_used);
+        Some(entry)
+    }
+}
+
+/// An in-memory [`SessionCache`] which evicts the least recently used entry
+/// once it reaches its capacity.
+pub struct LruSessionCache {
+    capacity: usize,
+    entries: Mutex<LruEntries>,
+}
+
+impl LruSessionCache {
+    /// Create a cache which stores at most `capacity` sessions.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::default(),
+        }
+    }
+
+    /// The number of sessions currently stored, including any expired
+    /// sessions that haven't been evicted yet.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Returns `true` if no sessions are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LruEntries> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl SessionCache for LruSessionCache {
+    fn store(
+        &self,
+        _connection: &mut Connection,
+        ttl: Duration,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<(), Error> {
+        if self.capacity == 0 {
+            return Ok(());
+        }
+        let expiration = Instant::now()
+            .checked_add(ttl)
+            .ok_or(Error::INVALID_INPUT)?;
+
+        let mut lru = self.lock();
+        lru.remove(key);
+        while lru.entries.len() >= self.capacity {
+            let oldest = match lru.usage.pop_first() {
+                Some((_, oldest)) => oldest,
+                None => break,
+            };
+            lru.entries.remove(&oldest);
+        }
+
+        let last_used = lru.next_use();
+        lru.usage.insert(last_used, key.to_vec());
+        let entry = CacheEntry {
+            value: value.to_vec(),
+            expiration,
+            last_used,
+        };
+        lru.entries.insert(key.to_vec(), entry);
+        Ok(())
+    }
+
+    fn retrieve(
+        &self,
+        _connection: &mut Connection,
+        key: &[u8],
+    ) -> Poll<Result<Option<Vec<u8>>, Error>> {
+        let mut lru = self.lock();
+        let mut entry = match lru.remove(key) {
+            Some(entry) => entry,
+            None => return Poll::Ready(Ok(None)),
+        };
+        if entry.expiration <= Instant::now() {
+            return Poll::Ready(Ok(None));
+        }
+
+        entry.last_used = lru.next_use();
+        lru.usage.insert(entry.last_used, key.to_vec());
+        let value = entry.value.clone();
+        lru.entries.insert(key.to_vec(), entry);
+        Poll::Ready(Ok(Some(value)))
+    }
+
+    fn delete(&self, _connection: &mut Connection, key: &[u8]) -> Result<(), Error> {
+        self.lock().remove(key);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, security, testing::*};
+    use futures_test::task::new_count_waker;
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::SystemTime,
+    };
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn lru_eviction() -> Result<(), Error> {
+        let mut conn = Connection::new_server();
+        let cache = LruSessionCache::new(2);
+        assert!(cache.is_empty());
+
+        cache.store(&mut conn, TTL, b"a", b"value a")?;
+        cache.store(&mut conn, TTL, b"b", b"value b")?;
+        // Using "a" makes "b" the least recently used entry
+        assert!(matches!(
+            cache.retrieve(&mut conn, b"a"),
+            Poll::Ready(Ok(Some(v))) if v == b"value a"
+        ));
+        cache.store(&mut conn, TTL, b"c", b"value c")?;
+
+        assert_eq!(cache.len(), 2);
+        assert!(matches!(
+            cache.retrieve(&mut conn, b"b"),
+            Poll::Ready(Ok(None))
+        ));
+        assert!(matches!(
+            cache.retrieve(&mut conn, b"a"),
+            Poll::Ready(Ok(Some(_)))
+        ));
+        assert!(matches!(
+            cache.retrieve(&mut conn, b"c"),
+            Poll::Ready(Ok(Some(_)))
+        ));
+
+        cache.delete(&mut conn, b"c")?;
+        assert!(matches!(
+            cache.retrieve(&mut conn, b"c"),
+            Poll::Ready(Ok(None))
+        ));
+        assert_eq!(cache.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn lru_replace_and_expire() -> Result<(), Error> {
+        let mut conn = Connection::new_server();
+        let cache = LruSessionCache::new(2);
+
+        cache.store(&mut conn, TTL, b"a", b"old")?;
+        cache.store(&mut conn, TTL, b"a", b"new")?;
+        assert_eq!(cache.len(), 1);
+        assert!(matches!(
+            cache.retrieve(&mut conn, b"a"),
+            Poll::Ready(Ok(Some(v))) if v == b"new"
+        ));
+
+        cache.store(&mut conn, Duration::ZERO, b"expired", b"value")?;
+        assert!(matches!(
+            cache.retrieve(&mut conn, b"expired"),
+            Poll::Ready(Ok(None))
+        ));
+        assert_eq!(cache.len(), 1);
+        Ok(())
+    }
+
+    const KEY: [u8; 16] = [0; 16];
+    const KEYNAME: [u8; 3] = [1, 3, 4];
+
+    fn cache_configs<T: SessionCache>(cache: T) -> Result<(Config, Config), Error> {
+        let keypair = CertKeyPair::default();
+
+        let mut server = crate::config::Builder::new();
+        server
+            .add_session_ticket_key(&KEYNAME, &KEY, SystemTime::now())?
+            .set_session_cache(cache)?
+            .set_security_policy(&security::TESTING_TLS12)?
+            .load_pem(keypair.cert(), keypair.key())?;
+
+        // The client doesn't enable session tickets, so resumes by session ID.
+        let mut client = crate::config::Builder::new();
+        client
+            .trust_pem(keypair.cert())?
+            .set_verify_host_callback(InsecureAcceptAllCertificatesHandler {})?
+            .set_security_policy(&security::TESTING_TLS12)?;
+
+        Ok((client.build()?, server.build()?))
+    }
+
+    /// Perform a full handshake, then resume the session it established.
+    fn handshake_and_resume(client: &Config, server: &Config) -> Result<TestPair, Error> {
+        let mut pair = TestPair::from_configs(client, server);
+        pair.handshake()?;
+        assert!(!pair.client.resumed());
+
+        let mut session = vec![0; pair.client.session_ticket_length()?];
+        pair.client.session_ticket(&mut session)?;
+
+        let mut pair = TestPair::from_configs(client, server);
+        pair.client.set_session_ticket(&session)?;
+        Ok(pair)
+    }
+
+    #[test]
+    fn resume_from_cache() -> Result<(), Error> {
+        let (client, server) = cache_configs(LruSessionCache::new(10))?;
+        let mut pair = handshake_and_resume(&client, &server)?;
+        pair.handshake()?;
+        assert!(pair.client.resumed());
+        assert!(pair.server.resumed());
+        Ok(())
+    }
+
+    #[test]
+    fn cache_miss() -> Result<(), Error> {
+        // A cache without capacity never stores a session
+        let (client, server) = cache_configs(LruSessionCache::new(0))?;
+        let mut pair = handshake_and_resume(&client, &server)?;
+        pair.handshake()?;
+        assert!(!pair.client.resumed());
+        assert!(!pair.server.resumed());
+        Ok(())
+    }
+
+    #[test]
+    fn async_retrieve() -> Result<(), Error> {
+        const POLL_COUNT: usize = 10;
+
+        /// Only returns entries after being polled POLL_COUNT times,
+        /// once `delay` is set.
+        struct SlowCache {
+            cache: LruSessionCache,
+            polls: Counter,
+            delay: Arc<AtomicBool>,
+        }
+
+        impl SessionCache for SlowCache {
+            fn store(
+                &self,
+                conn: &mut Connection,
+                ttl: Duration,
+                key: &[u8],
+                value: &[u8],
+            ) -> Result<(), Error> {
+                self.cache.store(conn, ttl, key, value)
+            }
+
+            fn retrieve(
+                &self,
+                conn: &mut Connection,
+                key: &[u8],
+            ) -> Poll<Result<Option<Vec<u8>>, Error>> {
+                if self.delay.load(Ordering::SeqCst) {
+                    self.polls.increment();
+                    if self.polls.count() < POLL_COUNT {
+                        conn.waker().unwrap().wake_by_ref();
+                        return Poll::Pending;
+                    }
+                }
+                self.cache.retrieve(conn, key)
+            }
+
+            fn delete(&self, conn: &mut Connection, key: &[u8]) -> Result<(), Error> {
+                self.cache.delete(conn, key)
+            }
+        }
+
+        let delay = Arc::new(AtomicBool::new(false));
+        let cache = SlowCache {
+            cache: LruSessionCache::new(10),
+            polls: Counter::default(),
+            delay: delay.clone(),
+        };
+        let (client, server) = cache_configs(cache)?;
+        let mut pair = handshake_and_resume(&client, &server)?;
+
+        delay.store(true, Ordering::SeqCst);
+        let (waker, wake_count) = new_count_waker();
+        pair.server.set_waker(Some(&waker))?;
+        pair.handshake()?;
+
+        assert_eq!(wake_count, POLL_COUNT - 1);
+        assert!(pair.client.resumed());
+        assert!(pair.server.resumed());
+        Ok(())
+    }
+}
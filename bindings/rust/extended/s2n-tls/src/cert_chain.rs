@@ -165,6 +165,25 @@ impl Builder {
         Ok(self)
     }
 
+    /// Sets the signed certificate timestamp (SCT) list sent to clients that
+    /// request Certificate Transparency.
+    ///
+    /// `data` must be an encoded `SignedCertificateTimestampList`, as defined in
+    /// [RFC 6962](https://www.rfc-editor.org/rfc/rfc6962#section-3.3).
+    ///
+    /// Corresponds to [s2n_cert_chain_and_key_set_sct_list].
+    pub fn set_sct_list(&mut self, data: &[u8]) -> Result<&mut Self, Error> {
+        unsafe {
+            s2n_cert_chain_and_key_set_sct_list(
+                self.cert_handle.cert.as_ptr(),
+                data.as_ptr(),
+                data.len().try_into().map_err(|_| Error::INVALID_INPUT)?,
+            )
+            .into_result()
+        }?;
+        Ok(self)
+    }
+
     /// Associates an arbitrary application context with the CertificateChain to
     /// be later retrieved via [`CertificateChain::application_context()`].
     ///
@@ -352,6 +371,10 @@ impl Certificate<'_> {
             Ok(std::slice::from_raw_parts(buffer, length))
         }
     }
+
+    pub(crate) fn as_ptr(&self) -> *mut s2n_cert {
+        self.certificate.as_ptr()
+    }
 }
 
 // # Safety
@@ -1,10 +1,17 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::error::{Error, Fallible};
+use crate::{
+    enums::Version,
+    error::{Error, Fallible},
+};
+use core::convert::TryInto;
 use s2n_tls_sys::*;
 use std::fmt;
 
+mod parse;
+pub use parse::*;
+
 // ClientHello is an opaque wrapper struct around `s2n_client_hello`. Note that
 // the size of this type is not known, and as such it can only be used through
 // references and pointers.
@@ -120,6 +127,223 @@ impl ClientHello {
         };
         Ok(raw_message)
     }
+
+    /// The raw cipher suite list sent by the client.
+    ///
+    /// Each cipher suite is a 2-byte IANA value, except for SSLv2 ClientHellos
+    /// which use 3-byte values. Use
+    /// [Connection::client_hello_is_sslv2](`crate::connection::Connection::client_hello_is_sslv2()`)
+    /// to detect SSLv2 ClientHellos.
+    ///
+    /// Corresponds to [s2n_client_hello_get_cipher_suites], but also
+    /// calls [s2n_client_hello_get_cipher_suites_length].
+    pub fn cipher_suites(&self) -> Result<Vec<u8>, Error> {
+        let length = unsafe {
+            s2n_client_hello_get_cipher_suites_length(self.deref_mut_ptr()).into_result()?
+        };
+
+        let mut cipher_suites = vec![0; length];
+        let out_length = unsafe {
+            s2n_client_hello_get_cipher_suites(
+                self.deref_mut_ptr(),
+                cipher_suites.as_mut_ptr(),
+                length.try_into().map_err(|_| Error::INVALID_INPUT)?,
+            )
+            .into_result()?
+        };
+        cipher_suites.truncate(out_length);
+        Ok(cipher_suites)
+    }
+
+    /// The extensions sent by the client, in the order they were sent.
+    ///
+    /// Corresponds to [s2n_client_hello_get_extensions], but also
+    /// calls [s2n_client_hello_get_extensions_length].
+    pub fn extensions(&self) -> Result<Extensions, Error> {
+        let length =
+            unsafe { s2n_client_hello_get_extensions_length(self.deref_mut_ptr()).into_result()? };
+
+        let mut extensions = vec![0; length];
+        let out_length = unsafe {
+            s2n_client_hello_get_extensions(
+                self.deref_mut_ptr(),
+                extensions.as_mut_ptr(),
+                length.try_into().map_err(|_| Error::INVALID_INPUT)?,
+            )
+            .into_result()?
+        };
+        extensions.truncate(out_length);
+        Ok(Extensions(extensions))
+    }
+
+    /// Returns the data of the extension with the given IANA value,
+    /// or `None` if the client did not send the extension.
+    ///
+    /// Unlike [s2n_client_hello_get_extension_by_id], this is not limited
+    /// to the extensions supported by s2n-tls.
+    pub fn extension(&self, extension_type: u16) -> Result<Option<Vec<u8>>, Error> {
+        if !self.has_extension(extension_type)? {
+            return Ok(None);
+        }
+        Ok(self.extensions()?.get(extension_type).map(<[u8]>::to_vec))
+    }
+
+    /// Corresponds to [s2n_client_hello_has_extension].
+    pub fn has_extension(&self, extension_type: u16) -> Result<bool, Error> {
+        let mut exists = false;
+        unsafe {
+            s2n_client_hello_has_extension(self.deref_mut_ptr(), extension_type, &mut exists)
+                .into_result()?;
+        }
+        Ok(exists)
+    }
+
+    /// The IANA values of the groups sent in the supported_groups extension.
+    ///
+    /// Fails if the client did not send the extension.
+    ///
+    /// Corresponds to [s2n_client_hello_get_supported_groups].
+    pub fn supported_groups(&self) -> Result<Vec<u16>, Error> {
+        let length = unsafe {
+            s2n_client_hello_get_extension_length(
+                self.deref_mut_ptr(),
+                s2n_tls_extension_type::SUPPORTED_GROUPS,
+            )
+            .into_result()?
+        };
+        // The extension data is a 2-byte length followed by 2-byte groups
+        let max_count = length / 2;
+
+        let mut groups = vec![0; max_count];
+        let mut count = 0;
+        unsafe {
+            s2n_client_hello_get_supported_groups(
+                self.deref_mut_ptr(),
+                groups.as_mut_ptr(),
+                max_count.try_into().map_err(|_| Error::INVALID_INPUT)?,
+                &mut count,
+            )
+            .into_result()?;
+        }
+        groups.truncate(count.into());
+        Ok(groups)
+    }
+
+    /// Corresponds to [s2n_client_hello_get_compression_methods], but also
+    /// calls [s2n_client_hello_get_compression_methods_length].
+    pub fn compression_methods(&self) -> Result<Vec<u8>, Error> {
+        let mut length = 0;
+        unsafe {
+            s2n_client_hello_get_compression_methods_length(self.deref_mut_ptr(), &mut length)
+                .into_result()?;
+        }
+
+        let mut methods = vec![0; length as usize];
+        let mut out_length = 0;
+        unsafe {
+            s2n_client_hello_get_compression_methods(
+                self.deref_mut_ptr(),
+                methods.as_mut_ptr(),
+                length,
+                &mut out_length,
+            )
+            .into_result()?;
+        }
+        methods.truncate(out_length as usize);
+        Ok(methods)
+    }
+
+    /// The protocol version field of the ClientHello.
+    ///
+    /// This field is no longer used to negotiate TLS1.3, and is set to TLS1.2
+    /// by TLS1.3 clients, so should only be used for logging or fingerprinting.
+    ///
+    /// Corresponds to [s2n_client_hello_get_legacy_protocol_version].
+    pub fn legacy_protocol_version(&self) -> Result<Version, Error> {
+        let mut version = 0;
+        unsafe {
+            s2n_client_hello_get_legacy_protocol_version(self.deref_mut_ptr(), &mut version)
+                .into_result()?;
+        }
+        s2n_tls_version::Type::from(version).try_into()
+    }
+
+    /// The protocol version from the header of the record containing the ClientHello.
+    ///
+    /// This is often set to TLS1.0 for compatibility, so should only be used
+    /// for logging or fingerprinting. Fails if the ClientHello wasn't received
+    /// in a record, for example if it was parsed with [`Self::parse_client_hello`].
+    ///
+    /// Corresponds to [s2n_client_hello_get_legacy_record_version].
+    pub fn legacy_record_version(&self) -> Result<Version, Error> {
+        let mut version = 0;
+        unsafe {
+            s2n_client_hello_get_legacy_record_version(self.deref_mut_ptr(), &mut version)
+                .into_result()?;
+        }
+        s2n_tls_version::Type::from(version).try_into()
+    }
+}
+
+/// The raw extensions sent in a ClientHello.
+///
+/// Iterating over the extensions returns the IANA value and data of each extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Extensions(Vec<u8>);
+
+impl Extensions {
+    /// Returns an iterator over the `(type, data)` of each extension.
+    pub fn iter(&self) -> ExtensionsIter<'_> {
+        ExtensionsIter { remaining: &self.0 }
+    }
+
+    /// Returns the data of the first extension with the given IANA value.
+    pub fn get(&self, extension_type: u16) -> Option<&[u8]> {
+        self.iter()
+            .find(|(ext_type, _)| *ext_type == extension_type)
+            .map(|(_, data)| data)
+    }
+
+    /// The extensions as they were sent on the wire.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<'a> IntoIterator for &'a Extensions {
+    type Item = (u16, &'a [u8]);
+    type IntoIter = ExtensionsIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the extensions in a ClientHello.
+///
+/// Created by [`Extensions::iter`].
+#[derive(Debug, Clone)]
+pub struct ExtensionsIter<'a> {
+    remaining: &'a [u8],
+}
+
+impl<'a> Iterator for ExtensionsIter<'a> {
+    type Item = (u16, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Each extension is a 2-byte type, a 2-byte length, then the data.
+        // s2n-tls already validated the extensions when parsing the ClientHello,
+        // so a truncated extension just ends iteration.
+        if self.remaining.len() < 4 {
+            return None;
+        }
+        let (header, rest) = self.remaining.split_at(4);
+        let extension_type = u16::from_be_bytes([header[0], header[1]]);
+        let length = u16::from_be_bytes([header[2], header[3]]) as usize;
+        let data = rest.get(..length)?;
+        self.remaining = &rest[length..];
+        Some((extension_type, data))
+    }
 }
 
 impl Drop for ClientHello {
@@ -152,7 +376,13 @@ pub use crate::fingerprint::FingerprintType;
 
 #[cfg(test)]
 mod tests {
-    use crate::client_hello::ClientHello;
+    use crate::{
+        client_hello::ClientHello,
+        enums::Version,
+        error::Error,
+        security,
+        testing::{build_config, TestPair},
+    };
 
     #[test]
     fn invalid_client_bytes() {
@@ -188,4 +418,81 @@ mod tests {
         let server_name = client_hello.server_name().unwrap();
         assert_eq!("incoming.telemetry.mozilla.org".as_bytes(), server_name);
     }
+
+    /// A ClientHello sent by Firefox
+    pub(crate) const FIREFOX_CLIENT_HELLO: &[u8] = &[
+        0x01, 0x00, 0x00, 0xEC, 0x03, 0x03, 0x90, 0xe8, 0xcc, 0xee, 0xe5, 0x70, 0xa2, 0xa1, 0x2f,
+        0x6b, 0x69, 0xd2, 0x66, 0x96, 0x0f, 0xcf, 0x20, 0xd5, 0x32, 0x6e, 0xc4, 0xb2, 0x8c, 0xc7,
+        0xbd, 0x0a, 0x06, 0xc2, 0xa5, 0x14, 0xfc, 0x34, 0x20, 0xaf, 0x72, 0xbf, 0x39, 0x99, 0xfb,
+        0x20, 0x70, 0xc3, 0x10, 0x83, 0x0c, 0xee, 0xfb, 0xfa, 0x72, 0xcc, 0x5d, 0xa8, 0x99, 0xb4,
+        0xc5, 0x53, 0xd6, 0x3d, 0xa0, 0x53, 0x7a, 0x5c, 0xbc, 0xf5, 0x0b, 0x00, 0x1e, 0xc0, 0x2b,
+        0xc0, 0x2f, 0xcc, 0xa9, 0xcc, 0xa8, 0xc0, 0x2c, 0xc0, 0x30, 0xc0, 0x0a, 0xc0, 0x09, 0xc0,
+        0x13, 0xc0, 0x14, 0x00, 0x33, 0x00, 0x39, 0x00, 0x2f, 0x00, 0x35, 0x00, 0x0a, 0x01, 0x00,
+        0x00, 0x85, 0x00, 0x00, 0x00, 0x23, 0x00, 0x21, 0x00, 0x00, 0x1e, 0x69, 0x6e, 0x63, 0x6f,
+        0x6d, 0x69, 0x6e, 0x67, 0x2e, 0x74, 0x65, 0x6c, 0x65, 0x6d, 0x65, 0x74, 0x72, 0x79, 0x2e,
+        0x6d, 0x6f, 0x7a, 0x69, 0x6c, 0x6c, 0x61, 0x2e, 0x6f, 0x72, 0x67, 0x00, 0x17, 0x00, 0x00,
+        0xff, 0x01, 0x00, 0x01, 0x00, 0x00, 0x0a, 0x00, 0x0a, 0x00, 0x08, 0x00, 0x1d, 0x00, 0x17,
+        0x00, 0x18, 0x00, 0x19, 0x00, 0x0b, 0x00, 0x02, 0x01, 0x00, 0x00, 0x23, 0x00, 0x00, 0x00,
+        0x10, 0x00, 0x0e, 0x00, 0x0c, 0x02, 0x68, 0x32, 0x08, 0x68, 0x74, 0x74, 0x70, 0x2f, 0x31,
+        0x2e, 0x31, 0x00, 0x05, 0x00, 0x05, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0d, 0x00, 0x18,
+        0x00, 0x16, 0x04, 0x03, 0x05, 0x03, 0x06, 0x03, 0x08, 0x04, 0x08, 0x05, 0x08, 0x06, 0x04,
+        0x01, 0x05, 0x01, 0x06, 0x01, 0x02, 0x03, 0x02, 0x01, 0x00, 0x1c, 0x00, 0x02, 0x40, 0x00,
+    ];
+
+    #[test]
+    fn cipher_suites_and_compression_methods() -> Result<(), Error> {
+        let client_hello = ClientHello::parse_client_hello(FIREFOX_CLIENT_HELLO)?;
+
+        let cipher_suites = client_hello.cipher_suites()?;
+        assert_eq!(cipher_suites.len(), 30);
+        // TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256
+        assert_eq!(cipher_suites[..2], [0xc0, 0x2b]);
+
+        assert_eq!(client_hello.compression_methods()?, [0]);
+        assert_eq!(client_hello.legacy_protocol_version()?, Version::TLS12);
+        // A parsed message was not received in a record
+        assert!(client_hello.legacy_record_version().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn extensions() -> Result<(), Error> {
+        let client_hello = ClientHello::parse_client_hello(FIREFOX_CLIENT_HELLO)?;
+
+        let extensions = client_hello.extensions()?;
+        let types: Vec<u16> = extensions.iter().map(|(ext_type, _)| ext_type).collect();
+        assert_eq!(
+            types,
+            [0x0000, 0x0017, 0xff01, 0x000a, 0x000b, 0x0023, 0x0010, 0x0005, 0x000d, 0x001c]
+        );
+        assert_eq!(extensions.get(0x000b), Some(&[0x01, 0x00][..]));
+
+        // record_size_limit isn't supported by s2n-tls, but can still be retrieved
+        assert!(client_hello.has_extension(0x001c)?);
+        assert_eq!(client_hello.extension(0x001c)?, Some(vec![0x40, 0x00]));
+        // extended_master_secret is empty
+        assert_eq!(client_hello.extension(0x0017)?, Some(vec![]));
+        // key_share was not sent
+        assert!(!client_hello.has_extension(0x0033)?);
+        assert_eq!(client_hello.extension(0x0033)?, None);
+
+        // x25519, secp256r1, secp384r1, secp521r1
+        assert_eq!(
+            client_hello.supported_groups()?,
+            [0x001d, 0x0017, 0x0018, 0x0019]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn legacy_record_version() -> Result<(), Error> {
+        let config = build_config(&security::DEFAULT_TLS13)?;
+        let mut pair = TestPair::from_config(&config);
+        pair.handshake()?;
+
+        let client_hello = pair.server.client_hello()?;
+        assert_eq!(client_hello.legacy_protocol_version()?, Version::TLS12);
+        assert_eq!(client_hello.legacy_record_version()?, Version::TLS10);
+        Ok(())
+    }
 }
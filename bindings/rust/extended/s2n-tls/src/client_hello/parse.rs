@@ -0,0 +1,219 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decoding of common ClientHello extensions.
+//!
+//! Each helper returns `Ok(None)` if the client did not send the extension,
+//! and an error if the extension was sent but is malformed.
+
+use super::ClientHello;
+use crate::{codec::Reader, error::Error};
+
+// https://www.iana.org/assignments/tls-extensiontype-values/tls-extensiontype-values.xhtml#tls-extensiontype-values-1
+const SERVER_NAME: u16 = 0;
+const SIGNATURE_ALGORITHMS: u16 = 13;
+const ALPN: u16 = 16;
+const PRE_SHARED_KEY: u16 = 41;
+const SUPPORTED_VERSIONS: u16 = 43;
+const PSK_KEY_EXCHANGE_MODES: u16 = 45;
+const KEY_SHARE: u16 = 51;
+
+/// The `host_name` server name type from RFC 6066.
+const HOST_NAME: u8 = 0;
+
+/// A PSK identity offered in the pre_shared_key extension.
+///
+/// Corresponds to the `PskIdentity` struct from
+/// [RFC 8446](https://www.rfc-editor.org/rfc/rfc8446#section-4.2.11).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PskIdentity {
+    identity: Vec<u8>,
+    obfuscated_ticket_age: u32,
+}
+
+impl PskIdentity {
+    /// The label for a key: either a resumption ticket or an external PSK identity.
+    pub fn identity(&self) -> &[u8] {
+        &self.identity
+    }
+
+    /// The age of a resumption ticket, obfuscated by the ticket's age_add.
+    ///
+    /// External PSKs should set this to 0.
+    pub fn obfuscated_ticket_age(&self) -> u32 {
+        self.obfuscated_ticket_age
+    }
+}
+
+impl ClientHello {
+    /// Retrieves an extension and decodes it with `decode`.
+    fn decode_extension<T>(
+        &self,
+        extension_type: u16,
+        decode: impl FnOnce(&mut Reader) -> Result<T, Error>,
+    ) -> Result<Option<T>, Error> {
+        let data = match self.extension(extension_type)? {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+        let mut reader = Reader(&data);
+        let value = decode(&mut reader)?;
+        reader.finish(value).map(Some)
+    }
+
+    /// The host names sent in the server_name extension.
+    ///
+    /// Unlike [`Self::server_name`], this returns every host name, not just the first.
+    pub fn server_names(&self) -> Result<Option<Vec<Vec<u8>>>, Error> {
+        self.decode_extension(SERVER_NAME, |reader| {
+            let names = reader.u16_prefixed()?.items(|name| {
+                let name_type = name.u8()?;
+                let host_name = name.u16_prefixed()?.0.to_vec();
+                Ok((name_type, host_name))
+            })?;
+            Ok(names
+                .into_iter()
+                .filter(|(name_type, _)| *name_type == HOST_NAME)
+                .map(|(_, host_name)| host_name)
+                .collect())
+        })
+    }
+
+    /// The protocols sent in the application_layer_protocol_negotiation extension,
+    /// in the client's order of preference.
+    pub fn application_protocols(&self) -> Result<Option<Vec<Vec<u8>>>, Error> {
+        self.decode_extension(ALPN, |reader| {
+            reader
+                .u16_prefixed()?
+                .items(|protocol| Ok(protocol.u8_prefixed()?.0.to_vec()))
+        })
+    }
+
+    /// The IANA values of the protocol versions sent in the supported_versions
+    /// extension, for example `0x0304` for TLS1.3.
+    pub fn supported_versions(&self) -> Result<Option<Vec<u16>>, Error> {
+        self.decode_extension(SUPPORTED_VERSIONS, |reader| {
+            reader.u8_prefixed()?.items(Reader::u16)
+        })
+    }
+
+    /// The IANA values of the signature schemes sent in the
+    /// signature_algorithms extension.
+    pub fn signature_algorithms(&self) -> Result<Option<Vec<u16>>, Error> {
+        self.decode_extension(SIGNATURE_ALGORITHMS, |reader| {
+            reader.u16_prefixed()?.items(Reader::u16)
+        })
+    }
+
+    /// The IANA values of the groups that the client sent key shares for
+    /// in the key_share extension.
+    pub fn key_share_groups(&self) -> Result<Option<Vec<u16>>, Error> {
+        self.decode_extension(KEY_SHARE, |reader| {
+            reader.u16_prefixed()?.items(|share| {
+                let group = share.u16()?;
+                share.u16_prefixed()?;
+                Ok(group)
+            })
+        })
+    }
+
+    /// The modes sent in the psk_key_exchange_modes extension:
+    /// `0` for psk_ke and `1` for psk_dhe_ke.
+    pub fn psk_key_exchange_modes(&self) -> Result<Option<Vec<u8>>, Error> {
+        self.decode_extension(PSK_KEY_EXCHANGE_MODES, |reader| {
+            Ok(reader.u8_prefixed()?.0.to_vec())
+        })
+    }
+
+    /// The identities offered in the pre_shared_key extension.
+    pub fn psk_identities(&self) -> Result<Option<Vec<PskIdentity>>, Error> {
+        self.decode_extension(PRE_SHARED_KEY, |reader| {
+            let identities = reader.u16_prefixed()?.items(|identity| {
+                Ok(PskIdentity {
+                    identity: identity.u16_prefixed()?.0.to_vec(),
+                    obfuscated_ticket_age: identity.u32()?,
+                })
+            })?;
+            // The binders aren't exposed, but must still be well formed.
+            reader
+                .u16_prefixed()?
+                .items(|binder| binder.u8_prefixed().map(|_| ()))?;
+            Ok(identities)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        client_hello::tests::FIREFOX_CLIENT_HELLO,
+        enums::PskHmac,
+        psk::Psk,
+        security,
+        testing::{build_config, TestPair},
+    };
+
+    #[test]
+    fn tls12_extensions() -> Result<(), Error> {
+        let client_hello = ClientHello::parse_client_hello(FIREFOX_CLIENT_HELLO)?;
+
+        assert_eq!(
+            client_hello.server_names()?,
+            Some(vec![b"incoming.telemetry.mozilla.org".to_vec()])
+        );
+        assert_eq!(
+            client_hello.application_protocols()?,
+            Some(vec![b"h2".to_vec(), b"http/1.1".to_vec()])
+        );
+        let signature_algorithms = client_hello.signature_algorithms()?.unwrap();
+        assert_eq!(signature_algorithms.len(), 11);
+        // ecdsa_secp256r1_sha256
+        assert_eq!(signature_algorithms[0], 0x0403);
+
+        // TLS1.3 extensions were not sent
+        assert_eq!(client_hello.supported_versions()?, None);
+        assert_eq!(client_hello.key_share_groups()?, None);
+        assert_eq!(client_hello.psk_key_exchange_modes()?, None);
+        assert_eq!(client_hello.psk_identities()?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn tls13_extensions() -> Result<(), Error> {
+        const IDENTITY: &[u8] = b"psk identity";
+        let psk = {
+            let mut psk = Psk::builder()?;
+            psk.set_identity(IDENTITY)?;
+            psk.set_secret(b"a secret that is at least 128 bits long")?;
+            psk.set_hmac(PskHmac::SHA256)?;
+            psk.build()?
+        };
+
+        let config = build_config(&security::DEFAULT_TLS13)?;
+        let mut pair = TestPair::from_config(&config);
+        pair.client.set_server_name("localhost")?;
+        pair.client.append_psk(&psk)?;
+        pair.server.append_psk(&psk)?;
+        pair.handshake()?;
+
+        let client_hello = pair.server.client_hello()?;
+        assert_eq!(
+            client_hello.server_names()?,
+            Some(vec![b"localhost".to_vec()])
+        );
+        assert!(client_hello
+            .supported_versions()?
+            .unwrap()
+            .contains(&0x0304));
+        assert!(!client_hello.key_share_groups()?.unwrap().is_empty());
+        // s2n-tls only supports psk_dhe_ke
+        assert_eq!(client_hello.psk_key_exchange_modes()?, Some(vec![1]));
+
+        let identities = client_hello.psk_identities()?.unwrap();
+        assert_eq!(identities.len(), 1);
+        assert_eq!(identities[0].identity(), IDENTITY);
+        assert_eq!(identities[0].obfuscated_ticket_age(), 0);
+        Ok(())
+    }
+}
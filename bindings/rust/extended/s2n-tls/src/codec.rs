@@ -0,0 +1,155 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Minimal decoding of TLS and DER wire formats, for structures that s2n-tls
+//! only exposes as raw bytes.
+
+use crate::error::{Error, ErrorType};
+
+const DECODE_ERROR: Error = Error::bindings(
+    ErrorType::ProtocolError,
+    "DecodeError",
+    "malformed or truncated TLS data",
+);
+
+/// A cursor over TLS-encoded data. All values are big-endian.
+pub(crate) struct Reader<'a>(pub(crate) &'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub(crate) fn bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < len {
+            return Err(DECODE_ERROR);
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, Error> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, Error> {
+        let mut value = [0; 8];
+        value.copy_from_slice(self.bytes(8)?);
+        Ok(u64::from_be_bytes(value))
+    }
+
+    /// Reads a vector prefixed with a 1-byte length.
+    pub(crate) fn u8_prefixed(&mut self) -> Result<Reader<'a>, Error> {
+        let len = self.u8()?;
+        Ok(Reader(self.bytes(len.into())?))
+    }
+
+    /// Reads a vector prefixed with a 2-byte length.
+    pub(crate) fn u16_prefixed(&mut self) -> Result<Reader<'a>, Error> {
+        let len = self.u16()?;
+        Ok(Reader(self.bytes(len.into())?))
+    }
+
+    /// Reads a DER element with the given tag and returns its contents.
+    pub(crate) fn der(&mut self, tag: u8) -> Result<Reader<'a>, Error> {
+        if self.u8()? != tag {
+            return Err(DECODE_ERROR);
+        }
+        let len = match self.u8()? {
+            short @ 0..=0x7f => usize::from(short),
+            // Long form lengths. Nothing decoded here needs more than 4 bytes.
+            long @ 0x81..=0x84 => {
+                let mut len = 0;
+                for byte in self.bytes(usize::from(long & 0x7f))? {
+                    len = (len << 8) | usize::from(*byte);
+                }
+                len
+            }
+            _ => return Err(DECODE_ERROR),
+        };
+        Ok(Reader(self.bytes(len)?))
+    }
+
+    /// Reads a DER element if the next element has the given tag.
+    pub(crate) fn der_optional(&mut self, tag: u8) -> Result<Option<Reader<'a>>, Error> {
+        if self.0.first() == Some(&tag) {
+            self.der(tag).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Skips the next DER element, whatever its tag.
+    pub(crate) fn der_skip(&mut self) -> Result<(), Error> {
+        let tag = *self.0.first().ok_or(DECODE_ERROR)?;
+        self.der(tag).map(|_| ())
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Decodes items until the reader is empty.
+    pub(crate) fn items<T>(
+        mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, Error>,
+    ) -> Result<Vec<T>, Error> {
+        let mut items = Vec::new();
+        while !self.is_empty() {
+            items.push(item(&mut self)?);
+        }
+        Ok(items)
+    }
+
+    /// Ensures that all of the data was consumed.
+    pub(crate) fn finish<T>(self, value: T) -> Result<T, Error> {
+        if self.is_empty() {
+            Ok(value)
+        } else {
+            Err(DECODE_ERROR)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reader_errors() {
+        // length prefix longer than the data
+        assert!(Reader(&[0, 3, 1, 2]).u16_prefixed().is_err());
+        // leftover data
+        assert!(Reader(&[1]).finish(()).is_err());
+        // partial item
+        assert!(Reader(&[0, 1, 0]).items(Reader::u16).is_err());
+        // unexpected DER tag
+        assert!(Reader(&[0x02, 1, 5]).der(0x30).is_err());
+        // DER length longer than the data
+        assert!(Reader(&[0x30, 0x81, 0x80, 0]).der(0x30).is_err());
+    }
+
+    #[test]
+    fn der() -> Result<(), Error> {
+        let mut long = vec![0x04, 0x82, 0x01, 0x00];
+        long.extend_from_slice(&[7; 256]);
+        assert_eq!(Reader(&long).der(0x04)?.0, [7; 256]);
+
+        // SEQUENCE { [0] { INTEGER 5 }, BOOLEAN true }
+        let mut reader = Reader(&[0x30, 0x08, 0xa0, 0x03, 0x02, 0x01, 0x05, 0x01, 0x01, 0xff]);
+        let mut sequence = reader.der(0x30)?;
+        assert!(sequence.der_optional(0xa1)?.is_none());
+        let mut explicit = sequence.der_optional(0xa0)?.unwrap();
+        assert_eq!(explicit.der(0x02)?.0, [5]);
+        sequence.der_skip()?;
+        assert!(sequence.is_empty());
+        reader.finish(())
+    }
+}
@@ -3,6 +3,8 @@
 
 #[cfg(feature = "unstable-cert_authorities")]
 use crate::cert_authorities::CertificateRequestCallback;
+#[cfg(feature = "unstable-crl")]
+use crate::crl::CrlLookupCallback;
 #[cfg(feature = "unstable-renegotiate")]
 use crate::renegotiate::RenegotiateCallback;
 use crate::{
@@ -283,6 +285,23 @@ impl Builder {
         Ok(self)
     }
 
+    /// Enables the Next Protocol Negotiation (NPN) extension.
+    ///
+    /// NPN was never standardized and was replaced by ALPN, but some older
+    /// peers only support NPN. The protocols offered are the same as for ALPN,
+    /// set with [`Self::set_application_protocol_preference()`]. If the peer
+    /// also supports ALPN, ALPN is preferred. NPN is not supported in TLS1.3.
+    ///
+    /// The negotiated protocol is reported by
+    /// [Connection::application_protocol](`crate::connection::Connection::application_protocol()`).
+    ///
+    /// Corresponds to [s2n_config_set_npn].
+    #[cfg(feature = "unstable-npn")]
+    pub fn enable_npn(&mut self, enable: bool) -> Result<&mut Self, Error> {
+        unsafe { s2n_config_set_npn(self.as_mut_ptr(), enable).into_result() }?;
+        Ok(self)
+    }
+
     /// Turns off x509 verification
     ///
     /// # Safety
@@ -313,14 +332,12 @@ impl Builder {
     pub fn load_pem(&mut self, certificate: &[u8], private_key: &[u8]) -> Result<&mut Self, Error> {
         let certificate = CString::new(certificate).map_err(|_| Error::INVALID_INPUT)?;
         let private_key = CString::new(private_key).map_err(|_| Error::INVALID_INPUT)?;
-        unsafe {
-            s2n_config_add_cert_chain_and_key(
-                self.as_mut_ptr(),
-                certificate.as_ptr(),
-                private_key.as_ptr(),
-            )
-            .into_result()
-        }?;
+        let config = self.as_mut_ptr();
+        let tiebreak = self.config.context().cert_tiebreak_callback.as_deref();
+        with_tiebreak_callback(tiebreak, || unsafe {
+            s2n_config_add_cert_chain_and_key(config, certificate.as_ptr(), private_key.as_ptr())
+                .into_result()
+        })?;
         Ok(self)
     }
 
@@ -331,15 +348,17 @@ impl Builder {
         // visibility into the failure modes, so this behavior ensures that _if_
         // the C library held the reference despite the failure, it would continue
         // to be valid memory.
-        let result = unsafe {
+        let config = self.as_mut_ptr();
+        let tiebreak = self.config.context().cert_tiebreak_callback.as_deref();
+        let result = with_tiebreak_callback(tiebreak, || unsafe {
             s2n_config_add_cert_chain_and_key_to_store(
-                self.as_mut_ptr(),
+                config,
                 // SAFETY: audit of add_to_store shows that the certificate chain
                 // is not mutated. https://github.com/aws/s2n-tls/issues/4140
                 chain.as_ptr() as *mut _,
             )
             .into_result()
-        };
+        });
         let context = unsafe {
             // SAFETY: usage of context_mut is safe in the builder, because the
             // Builder owns the only reference to the config.
@@ -351,6 +370,32 @@ impl Builder {
         Ok(self)
     }
 
+    /// Sets a callback to choose between certificate chains loaded for the same domain name.
+    ///
+    /// Without the callback, the first chain loaded for a domain name is used.
+    /// The callback is called while chains are loaded, so must be set before
+    /// [`Self::load_pem()`] or [`Self::load_chain()`] are called.
+    ///
+    /// Corresponds to [s2n_config_set_cert_tiebreak_callback].
+    pub fn set_cert_tiebreak_callback<T: 'static + CertTiebreakCallback>(
+        &mut self,
+        handler: T,
+    ) -> Result<&mut Self, Error> {
+        let handler = Box::new(handler);
+        let context = unsafe {
+            // SAFETY: usage of context_mut is safe in the builder, because while
+            // it is being built, the Builder is the only reference to the config.
+            self.config.context_mut()
+        };
+        context.cert_tiebreak_callback = Some(handler);
+
+        unsafe {
+            s2n_config_set_cert_tiebreak_callback(self.as_mut_ptr(), Some(cert_tiebreak_cb))
+                .into_result()?;
+        }
+        Ok(self)
+    }
+
     /// Corresponds to [s2n_config_set_cert_chain_and_key_defaults].
     pub fn set_default_chains<T: IntoIterator<Item = CertificateChain<'static>>>(
         &mut self,
@@ -514,6 +559,93 @@ impl Builder {
         Ok(self)
     }
 
+    /// Clients will validate stapled OCSP responses if `enable` is true.
+    ///
+    /// Validation is enabled by default whenever OCSP stapling is requested.
+    ///
+    /// Corresponds to [s2n_config_set_check_stapled_ocsp_response].
+    pub fn set_check_stapled_ocsp_response(&mut self, enable: bool) -> Result<&mut Self, Error> {
+        unsafe {
+            s2n_config_set_check_stapled_ocsp_response(self.as_mut_ptr(), enable as u8)
+                .into_result()
+        }?;
+        Ok(self)
+    }
+
+    /// Clients will fail the handshake if the server's certificate requires
+    /// a stapled OCSP response ("must-staple"), but the server doesn't send one.
+    ///
+    /// The check happens during certificate validation, so the certificate
+    /// is rejected and the handshake fails with `S2N_ERR_CERT_REJECTED`.
+    /// Enabling must-staple also enables OCSP stapling requests.
+    /// See [crate::ocsp] for details.
+    ///
+    /// Corresponds to [s2n_config_set_cert_validation_cb]. It can be combined
+    /// with [Self::set_cert_validation_callback_sync], which is only called for
+    /// certificates that pass the must-staple check.
+    #[cfg(feature = "unstable-crl")]
+    pub fn set_ocsp_must_staple(&mut self, enable: bool) -> Result<&mut Self, Error> {
+        if enable {
+            self.enable_ocsp()?;
+        }
+        let context = unsafe {
+            // SAFETY: usage of context_mut is safe in the builder, because while
+            // it is being built, the Builder is the only reference to the config.
+            self.config.context_mut()
+        };
+        context.ocsp_must_staple = enable;
+
+        unsafe {
+            s2n_config_set_cert_validation_cb(
+                self.as_mut_ptr(),
+                Some(cert_validation_cb),
+                core::ptr::null_mut(),
+            )
+            .into_result()?;
+        }
+        Ok(self)
+    }
+
+    /// Clients will request signed certificate timestamps (SCTs) from the server
+    /// if `level` is [CtSupportLevel::Request].
+    ///
+    /// See [crate::sct] for decoding the received SCTs.
+    ///
+    /// Corresponds to [s2n_config_set_ct_support_level].
+    pub fn set_ct_support_level(&mut self, level: CtSupportLevel) -> Result<&mut Self, Error> {
+        unsafe { s2n_config_set_ct_support_level(self.as_mut_ptr(), level.into()).into_result() }?;
+        Ok(self)
+    }
+
+    /// Clients will request that the server limit the size of the records it sends.
+    ///
+    /// Servers only honor the request if they opt in with
+    /// [`Self::accept_max_fragment_length()`], in which case both peers limit
+    /// the records they send to `length`. With the `unstable-max_fragment_length`
+    /// feature, the negotiated limit can be retrieved with
+    /// [Connection::negotiated_max_fragment_length](`crate::connection::Connection::negotiated_max_fragment_length()`).
+    ///
+    /// Corresponds to [s2n_config_send_max_fragment_length].
+    pub fn send_max_fragment_length(
+        &mut self,
+        length: MaxFragmentLength,
+    ) -> Result<&mut Self, Error> {
+        unsafe {
+            s2n_config_send_max_fragment_length(self.as_mut_ptr(), length.into()).into_result()
+        }?;
+        Ok(self)
+    }
+
+    /// Servers will accept client requests to limit the size of records.
+    ///
+    /// Without this, servers ignore the requests.
+    ///
+    /// Corresponds to [s2n_config_accept_max_fragment_length].
+    pub fn accept_max_fragment_length(&mut self) -> Result<&mut Self, Error> {
+        unsafe { s2n_config_accept_max_fragment_length(self.as_mut_ptr()).into_result() }?;
+        Ok(self)
+    }
+
     /// Sets the OCSP data for the default certificate chain associated with the Config.
     ///
     /// Servers will send the data in response to OCSP stapling requests from clients.
@@ -632,25 +764,6 @@ impl Builder {
         &mut self,
         handler: T,
     ) -> Result<&mut Self, Error> {
-        unsafe extern "C" fn cert_validation_cb(
-            conn_ptr: *mut s2n_connection,
-            validation_info: *mut s2n_cert_validation_info,
-            _context: *mut core::ffi::c_void,
-        ) -> libc::c_int {
-            let mut info = CertValidationInfo::from_ptr(validation_info);
-            with_context(conn_ptr, |conn, context| {
-                let callback = context.cert_validation_callback_sync.as_ref();
-                callback.map(|callback| {
-                    let accepted = callback.handle_validation(conn, &mut info).unwrap();
-                    match accepted {
-                        true => info.accept().unwrap(),
-                        false => info.reject().unwrap(),
-                    }
-                })
-            });
-            CallbackResult::Success.into()
-        }
-
         let handler = Box::new(handler);
         let context = unsafe {
             // SAFETY: usage of context_mut is safe in the builder, because while
@@ -712,6 +825,60 @@ impl Builder {
         Ok(self)
     }
 
+    /// Sets the maximum bytes of early data the server will accept.
+    ///
+    /// The default maximum is 0, which rejects all early data requests.
+    /// Setting a non-zero maximum causes the server to issue session tickets
+    /// that support early data.
+    ///
+    /// Corresponds to [s2n_config_set_server_max_early_data_size].
+    pub fn set_server_max_early_data_size(&mut self, size: u32) -> Result<&mut Self, Error> {
+        unsafe {
+            s2n_config_set_server_max_early_data_size(self.as_mut_ptr(), size).into_result()
+        }?;
+        Ok(self)
+    }
+
+    /// Set a custom callback function which is run to accept or reject early data.
+    ///
+    /// If no callback is set, early data is accepted whenever it is valid.
+    ///
+    /// Corresponds to [s2n_config_set_early_data_cb].
+    pub fn set_early_data_callback<T: 'static + EarlyDataCallback>(
+        &mut self,
+        handler: T,
+    ) -> Result<&mut Self, Error> {
+        unsafe extern "C" fn early_data_cb(
+            conn_ptr: *mut s2n_connection,
+            early_data_ptr: *mut s2n_offered_early_data,
+        ) -> libc::c_int {
+            with_context(conn_ptr, |conn, context| {
+                let early_data = OfferedEarlyData::from_cb(early_data_ptr);
+                let callback = context.early_data_callback.as_ref();
+                let future_result = early_data.and_then(|early_data| {
+                    callback.map_or(Ok(None), |callback| {
+                        callback.on_early_data(conn, early_data)
+                    })
+                });
+                AsyncCallback::trigger(future_result, conn)
+            })
+            .into()
+        }
+
+        let handler = Box::new(handler);
+        let context = unsafe {
+            // SAFETY: usage of context_mut is safe in the builder, because while
+            // it is being built, the Builder is the only reference to the config.
+            self.config.context_mut()
+        };
+        context.early_data_callback = Some(handler);
+
+        unsafe {
+            s2n_config_set_early_data_cb(self.as_mut_ptr(), Some(early_data_cb)).into_result()?;
+        }
+        Ok(self)
+    }
+
     pub fn set_connection_initializer<T: 'static + ConnectionInitializer>(
         &mut self,
         handler: T,
@@ -771,6 +938,120 @@ impl Builder {
         Ok(self)
     }
 
+    /// Sets the cache a server uses to resume TLS1.2 sessions by session ID.
+    ///
+    /// Cache entries are encrypted with the session ticket keys, so at least one
+    /// key must also be added with [Builder::add_session_ticket_key].
+    ///
+    /// Corresponds to [s2n_config_set_cache_store_callback],
+    /// [s2n_config_set_cache_retrieve_callback], and [s2n_config_set_cache_delete_callback],
+    /// and also calls [s2n_config_set_session_cache_onoff].
+    pub fn set_session_cache<T: 'static + SessionCache>(
+        &mut self,
+        cache: T,
+    ) -> Result<&mut Self, Error> {
+        unsafe extern "C" fn cache_store_cb(
+            conn_ptr: *mut s2n_connection,
+            _context: *mut ::libc::c_void,
+            ttl_in_seconds: u64,
+            key: *const ::libc::c_void,
+            key_size: u64,
+            value: *const ::libc::c_void,
+            value_size: u64,
+        ) -> libc::c_int {
+            let key = core::slice::from_raw_parts(key as *const u8, key_size as usize);
+            let value = core::slice::from_raw_parts(value as *const u8, value_size as usize);
+            let ttl = Duration::from_secs(ttl_in_seconds);
+            with_context(conn_ptr, |conn, context| {
+                let cache = context.session_cache.as_ref();
+                match cache.map(|cache| cache.store(conn, ttl, key, value)) {
+                    Some(Ok(())) => CallbackResult::Success,
+                    _ => CallbackResult::Failure,
+                }
+            })
+            .into()
+        }
+
+        unsafe extern "C" fn cache_retrieve_cb(
+            conn_ptr: *mut s2n_connection,
+            _context: *mut ::libc::c_void,
+            key: *const ::libc::c_void,
+            key_size: u64,
+            value: *mut ::libc::c_void,
+            value_size: *mut u64,
+        ) -> libc::c_int {
+            let key = core::slice::from_raw_parts(key as *const u8, key_size as usize);
+            let result = with_context(conn_ptr, |conn, context| {
+                let cache = match context.session_cache.as_ref() {
+                    Some(cache) => cache,
+                    None => return Poll::Ready(CallbackResult::Failure),
+                };
+                let entry = match cache.retrieve(conn, key) {
+                    Poll::Ready(Ok(Some(entry))) => entry,
+                    Poll::Ready(_) => return Poll::Ready(CallbackResult::Failure),
+                    Poll::Pending => return Poll::Pending,
+                };
+                // *value_size is initially the space available for the entry
+                if entry.len() as u64 > *value_size {
+                    return Poll::Ready(CallbackResult::Failure);
+                }
+                core::ptr::copy_nonoverlapping(entry.as_ptr(), value as *mut u8, entry.len());
+                *value_size = entry.len() as u64;
+                Poll::Ready(CallbackResult::Success)
+            });
+            CallbackResult::poll_into(result)
+        }
+
+        unsafe extern "C" fn cache_delete_cb(
+            conn_ptr: *mut s2n_connection,
+            _context: *mut ::libc::c_void,
+            key: *const ::libc::c_void,
+            key_size: u64,
+        ) -> libc::c_int {
+            let key = core::slice::from_raw_parts(key as *const u8, key_size as usize);
+            with_context(conn_ptr, |conn, context| {
+                let cache = context.session_cache.as_ref();
+                match cache.map(|cache| cache.delete(conn, key)) {
+                    Some(Ok(())) => CallbackResult::Success,
+                    _ => CallbackResult::Failure,
+                }
+            })
+            .into()
+        }
+
+        let cache = Box::new(cache);
+        let context = unsafe {
+            // SAFETY: usage of context_mut is safe in the builder, because while
+            // it is being built, the Builder is the only reference to the config.
+            self.config.context_mut()
+        };
+        context.session_cache = Some(cache);
+
+        unsafe {
+            let config = self.as_mut_ptr();
+            s2n_config_set_cache_store_callback(
+                config,
+                Some(cache_store_cb),
+                core::ptr::null_mut(),
+            )
+            .into_result()?;
+            s2n_config_set_cache_retrieve_callback(
+                config,
+                Some(cache_retrieve_cb),
+                core::ptr::null_mut(),
+            )
+            .into_result()?;
+            s2n_config_set_cache_delete_callback(
+                config,
+                Some(cache_delete_cb),
+                core::ptr::null_mut(),
+            )
+            .into_result()?;
+            s2n_config_set_session_cache_onoff(config, 1).into_result()?;
+        }
+        Ok(self)
+    }
+
     /// Set a callback function triggered by operations requiring the private key.
     ///
     /// See https://github.com/aws/s2n-tls/blob/main/docs/USAGE-GUIDE.md#private-key-operation-related-calls
@@ -810,6 +1091,100 @@ impl Builder {
         Ok(self)
     }
 
+    /// Set a callback to offload expensive operations, like signature
+    /// verification, from the task driving the handshake.
+    ///
+    /// Only operations of the types in `allowed` are passed to the callback.
+    /// All other operations are performed synchronously during the handshake.
+    ///
+    /// Corresponds to [s2n_config_set_async_offload_callback].
+    #[cfg(feature = "unstable-async_offload")]
+    pub fn set_async_offload_callback<T: 'static + AsyncOffloadCallback>(
+        &mut self,
+        handler: T,
+        allowed: &[OffloadOperationType],
+    ) -> Result<&mut Self, Error> {
+        let allow_list = allowed.iter().fold(0, |list, op_type| {
+            list | s2n_async_offload_op_type::Type::from(*op_type)
+        });
+
+        let handler = Box::new(handler);
+        let context = unsafe {
+            // SAFETY: usage of context_mut is safe in the builder, because while
+            // it is being built, the Builder is the only reference to the config.
+            self.config.context_mut()
+        };
+        context.async_offload_callback = Some(handler);
+
+        unsafe {
+            s2n_config_set_async_offload_callback(
+                self.as_mut_ptr(),
+                allow_list as u32,
+                Some(async_offload_cb),
+                core::ptr::null_mut(),
+            )
+            .into_result()?;
+        }
+        Ok(self)
+    }
+
+    /// Set a callback used by a server to choose a PSK from the PSKs offered by the client.
+    ///
+    /// Without the callback, the server chooses the first offered PSK that matches
+    /// a PSK added with [Connection::append_psk](`crate::connection::Connection::append_psk()`).
+    ///
+    /// Corresponds to [s2n_config_set_psk_selection_callback].
+    pub fn set_psk_selection_callback<T: 'static + PskSelectionCallback>(
+        &mut self,
+        handler: T,
+    ) -> Result<&mut Self, Error> {
+        unsafe extern "C" fn psk_selection_cb(
+            conn_ptr: *mut s2n_connection,
+            _context: *mut ::libc::c_void,
+            psk_list: *mut s2n_offered_psk_list,
+        ) -> libc::c_int {
+            with_context(conn_ptr, |conn, context| {
+                let callback = context.psk_selection_callback.as_ref();
+                let result = OfferedPskList::from_cb(psk_list).and_then(|mut psk_list| {
+                    callback.map_or(Ok(()), |callback| callback.select_psk(conn, &mut psk_list))
+                });
+                CallbackResult::from(result)
+            })
+            .into()
+        }
+
+        let handler = Box::new(handler);
+        let context = unsafe {
+            // SAFETY: usage of context_mut is safe in the builder, because while
+            // it is being built, the Builder is the only reference to the config.
+            self.config.context_mut()
+        };
+        context.psk_selection_callback = Some(handler);
+
+        unsafe {
+            s2n_config_set_psk_selection_callback(
+                self.as_mut_ptr(),
+                Some(psk_selection_cb),
+                core::ptr::null_mut(),
+            )
+            .into_result()?;
+        }
+        Ok(self)
+    }
+
+    /// Sets which type of PSKs connections use by default: PSKs established by
+    /// session resumption, or external PSKs added with
+    /// [Connection::append_psk](`crate::connection::Connection::append_psk()`).
+    ///
+    /// The mode can be overridden for individual connections with
+    /// [Connection::set_psk_mode](`crate::connection::Connection::set_psk_mode()`).
+    ///
+    /// Corresponds to [s2n_config_set_psk_mode].
+    pub fn set_psk_mode(&mut self, mode: PskMode) -> Result<&mut Self, Error> {
+        unsafe { s2n_config_set_psk_mode(self.as_mut_ptr(), mode.into()).into_result() }?;
+        Ok(self)
+    }
+
     /// Set a callback function that will be used to get the system time.
     ///
     /// The wall clock time is the best-guess at the real time, measured since the epoch.
@@ -978,6 +1353,31 @@ impl Builder {
         Ok(self)
     }
 
+    /// Sets how long a session can be resumed for. The lifetime of each issued
+    /// session ticket is also limited by the lifetime of its ticket key.
+    ///
+    /// Corresponds to [s2n_config_set_session_state_lifetime].
+    pub fn set_session_state_lifetime(&mut self, lifetime: Duration) -> Result<&mut Self, Error> {
+        unsafe {
+            s2n_config_set_session_state_lifetime(self.as_mut_ptr(), lifetime.as_secs())
+                .into_result()
+        }?;
+        Ok(self)
+    }
+
+    /// Sets how many session tickets a server sends after a TLS1.3 handshake.
+    /// The default is one ticket.
+    ///
+    /// Clients that open many parallel connections can use a separate ticket
+    /// for each connection. More tickets can be sent later with
+    /// [Connection::add_new_tickets_to_send](`crate::connection::Connection::add_new_tickets_to_send()`).
+    ///
+    /// Corresponds to [s2n_config_set_initial_ticket_count].
+    pub fn set_initial_ticket_count(&mut self, count: u8) -> Result<&mut Self, Error> {
+        unsafe { s2n_config_set_initial_ticket_count(self.as_mut_ptr(), count).into_result() }?;
+        Ok(self)
+    }
+
     /// Sets the expected connection serialization version. Must be set
     /// before serializing the connection.
     ///
@@ -1085,17 +1485,27 @@ pub(crate) struct Context {
     application_owned_certs: Vec<CertificateChain<'static>>,
     pub(crate) client_hello_callback: Option<Box<dyn ClientHelloCallback>>,
     pub(crate) private_key_callback: Option<Box<dyn PrivateKeyCallback>>,
+    pub(crate) cert_tiebreak_callback: Option<Box<dyn CertTiebreakCallback>>,
+    pub(crate) psk_selection_callback: Option<Box<dyn PskSelectionCallback>>,
+    pub(crate) early_data_callback: Option<Box<dyn EarlyDataCallback>>,
     pub(crate) verify_host_callback: Option<Box<dyn VerifyHostNameCallback>>,
     pub(crate) session_ticket_callback: Option<Box<dyn SessionTicketCallback>>,
+    pub(crate) session_cache: Option<Box<dyn SessionCache>>,
     pub(crate) connection_initializer: Option<Box<dyn ConnectionInitializer>>,
     pub(crate) wall_clock: Option<Box<dyn WallClock>>,
     pub(crate) monotonic_clock: Option<Box<dyn MonotonicClock>>,
+    #[cfg(feature = "unstable-crl")]
+    pub(crate) ocsp_must_staple: bool,
     #[cfg(feature = "unstable-renegotiate")]
     pub(crate) renegotiate: Option<Box<dyn RenegotiateCallback>>,
     #[cfg(feature = "unstable-cert_authorities")]
     pub(crate) cert_authorities: Option<Box<dyn CertificateRequestCallback>>,
     #[cfg(feature = "unstable-crl")]
     pub(crate) cert_validation_callback_sync: Option<Box<dyn CertValidationCallbackSync>>,
+    #[cfg(feature = "unstable-crl")]
+    pub(crate) crl_lookup_callback: Option<Box<dyn CrlLookupCallback>>,
+    #[cfg(feature = "unstable-async_offload")]
+    pub(crate) async_offload_callback: Option<Box<dyn AsyncOffloadCallback>>,
 }
 
 impl Default for Context {
@@ -1109,17 +1519,27 @@ impl Default for Context {
             application_owned_certs: Vec::new(),
             client_hello_callback: None,
             private_key_callback: None,
+            cert_tiebreak_callback: None,
+            psk_selection_callback: None,
+            early_data_callback: None,
             verify_host_callback: None,
             session_ticket_callback: None,
+            session_cache: None,
             connection_initializer: None,
             wall_clock: None,
             monotonic_clock: None,
+            #[cfg(feature = "unstable-crl")]
+            ocsp_must_staple: false,
             #[cfg(feature = "unstable-renegotiate")]
             renegotiate: None,
             #[cfg(feature = "unstable-cert_authorities")]
             cert_authorities: None,
             #[cfg(feature = "unstable-crl")]
             cert_validation_callback_sync: None,
+            #[cfg(feature = "unstable-crl")]
+            crl_lookup_callback: None,
+            #[cfg(feature = "unstable-async_offload")]
+            async_offload_callback: None,
         }
     }
 }
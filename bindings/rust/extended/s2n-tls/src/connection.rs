@@ -5,6 +5,8 @@
 
 #[cfg(feature = "unstable-cert_authorities")]
 use crate::cert_authorities::CertRequestState;
+#[cfg(feature = "unstable-crl")]
+use crate::crl::CrlState;
 #[cfg(feature = "unstable-renegotiate")]
 use crate::renegotiate::RenegotiateState;
 use crate::{
@@ -28,7 +30,7 @@ use core::{
 };
 use libc::c_void;
 use s2n_tls_sys::*;
-use std::{any::Any, ffi::CStr};
+use std::{any::Any, ffi::CStr, io::IoSlice};
 
 mod builder;
 pub use builder::*;
@@ -48,6 +50,19 @@ macro_rules! const_str {
     };
 }
 
+/// [s2n_send_early_data] and [s2n_recv_early_data] may transfer some data
+/// before reporting that they are blocked. Report that progress instead of
+/// `Pending` so that the caller doesn't lose track of the transferred bytes.
+fn early_data_progress<T>(
+    result: Poll<Result<T, Error>>,
+    transferred: isize,
+) -> Poll<Result<(), Error>> {
+    match result {
+        Poll::Pending if transferred > 0 => Poll::Ready(Ok(())),
+        result => result.map_ok(|_| ()),
+    }
+}
+
 #[non_exhaustive]
 #[derive(Debug, PartialEq)]
 /// s2n-tls only tracks up to u8::MAX (255) key updates. If any of the fields show
@@ -225,6 +240,16 @@ impl Connection {
         Ok(self)
     }
 
+    /// Corresponds to [s2n_connection_get_client_auth_type].
+    pub fn client_auth_type(&self) -> Result<ClientAuthType, Error> {
+        let mut auth_type = s2n_cert_auth_type::NONE;
+        unsafe {
+            s2n_connection_get_client_auth_type(self.connection.as_ptr(), &mut auth_type)
+                .into_result()?;
+        }
+        auth_type.try_into()
+    }
+
     /// Attempts to drop the config on the connection.
     ///
     /// # Safety
@@ -498,6 +523,30 @@ impl Connection {
         Ok(self)
     }
 
+    /// The maximum fragment length negotiated with the peer, if any.
+    ///
+    /// Records sent on the connection will be no larger than the negotiated length.
+    ///
+    /// Corresponds to [s2n_connection_get_negotiated_max_fragment_length].
+    #[cfg(feature = "unstable-max_fragment_length")]
+    pub fn negotiated_max_fragment_length(&self) -> Result<Option<MaxFragmentLength>, Error> {
+        let mut negotiated = false;
+        let mut mfl_code = s2n_max_frag_len::LEN_512;
+        unsafe {
+            s2n_connection_get_negotiated_max_fragment_length(
+                self.connection.as_ptr(),
+                &mut negotiated,
+                &mut mfl_code,
+            )
+            .into_result()
+        }?;
+        if negotiated {
+            MaxFragmentLength::try_from(mfl_code).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
     /// Configure the connection to reduce potentially expensive calls to recv.
     ///
     /// Corresponds to [s2n_connection_set_recv_buffering].
@@ -603,7 +652,12 @@ impl Connection {
                 Poll::Ready(Ok(_)) => {}
             };
 
-            match negotiate(self) {
+            #[cfg(feature = "unstable-crl")]
+            let poll = self.with_crl_lookups(&mut negotiate);
+            #[cfg(not(feature = "unstable-crl"))]
+            let poll = negotiate(self);
+
+            match poll {
                 Poll::Ready(res) => return Poll::Ready(res.map(|_| ())),
                 Poll::Pending => {
                     // If `negotiate` returned `Pending` it could be blocked on a connection future
@@ -642,6 +696,77 @@ impl Connection {
         .map_ok(|_| self)
     }
 
+    /// Begins the TLS handshake and sends early data.
+    ///
+    /// Must be called by a client before [negotiate](`Self::poll_negotiate`).
+    ///
+    /// Returns the number of bytes written, and may indicate a partial write.
+    /// Returns `0` for a non-empty `buf` if no more early data can be sent,
+    /// either because the limit was reached or because the server rejected
+    /// early data. Any remaining data must be sent with
+    /// [send](`Self::poll_send`) after the handshake completes.
+    ///
+    /// Early data is not forward secret and may be replayed by an attacker.
+    /// See the [Usage Guide](https://github.com/aws/s2n-tls/blob/main/docs/usage-guide/topics/ch15-early-data.md)
+    /// before using it.
+    ///
+    /// Corresponds to [s2n_send_early_data].
+    pub fn poll_send_early_data(&mut self, buf: &[u8]) -> Poll<Result<usize, Error>> {
+        let buf_len: isize = buf.len().try_into().map_err(|_| Error::INVALID_INPUT)?;
+        let buf_ptr = buf.as_ptr();
+        let mut data_sent: isize = 0;
+        self.poll_negotiate_method(|conn| {
+            let mut blocked = s2n_blocked_status::NOT_BLOCKED;
+            let result = unsafe {
+                s2n_send_early_data(
+                    conn.as_ptr(),
+                    buf_ptr,
+                    buf_len,
+                    &mut data_sent,
+                    &mut blocked,
+                )
+                .into_poll()
+            };
+            early_data_progress(result, data_sent)
+        })
+        .map_ok(|_| data_sent as usize)
+    }
+
+    /// Begins the TLS handshake and receives any early data sent by the client.
+    ///
+    /// Must be called by a server before [negotiate](`Self::poll_negotiate`).
+    /// Once called, it must be called until it returns `0` to ensure that
+    /// all early data has been read.
+    ///
+    /// Returns the number of bytes read, and may indicate a partial read.
+    /// `0` bytes returned indicates that no more early data will be received.
+    ///
+    /// Early data is not forward secret and may be replayed by an attacker.
+    /// See the [Usage Guide](https://github.com/aws/s2n-tls/blob/main/docs/usage-guide/topics/ch15-early-data.md)
+    /// before using it.
+    ///
+    /// Corresponds to [s2n_recv_early_data].
+    pub fn poll_recv_early_data(&mut self, buf: &mut [u8]) -> Poll<Result<usize, Error>> {
+        let buf_len: isize = buf.len().try_into().map_err(|_| Error::INVALID_INPUT)?;
+        let buf_ptr = buf.as_mut_ptr();
+        let mut data_received: isize = 0;
+        self.poll_negotiate_method(|conn| {
+            let mut blocked = s2n_blocked_status::NOT_BLOCKED;
+            let result = unsafe {
+                s2n_recv_early_data(
+                    conn.as_ptr(),
+                    buf_ptr,
+                    buf_len,
+                    &mut data_received,
+                    &mut blocked,
+                )
+                .into_poll()
+            };
+            early_data_progress(result, data_received)
+        })
+        .map_ok(|_| data_received as usize)
+    }
+
     /// Encrypts and sends data on a connection where
     /// [negotiate](`Self::poll_negotiate`) has succeeded.
     ///
@@ -656,6 +781,34 @@ impl Connection {
         unsafe { s2n_send(self.connection.as_ptr(), buf_ptr, buf_len, &mut blocked).into_poll() }
     }
 
+    /// Encrypts and sends data from multiple buffers on a connection where
+    /// [negotiate](`Self::poll_negotiate`) has succeeded.
+    ///
+    /// Returns the number of bytes written, and may indicate a partial write.
+    /// As with [`Self::poll_send`], if the write is blocked then the same
+    /// buffers should be provided again.
+    ///
+    /// Corresponds to [s2n_sendv_with_offset].
+    pub fn poll_send_vectored(&mut self, bufs: &[IoSlice]) -> Poll<Result<usize, Error>> {
+        let count: isize = bufs.len().try_into().map_err(|_| Error::INVALID_INPUT)?;
+        // IoSlice is guaranteed to be ABI compatible with iovec on unix platforms
+        let bufs_ptr = bufs.as_ptr() as *const libc::iovec;
+        self.poll_sendv_raw(bufs_ptr, count)
+    }
+
+    #[cfg(not(feature = "unstable-renegotiate"))]
+    pub(crate) fn poll_sendv_raw(
+        &mut self,
+        bufs_ptr: *const libc::iovec,
+        count: isize,
+    ) -> Poll<Result<usize, Error>> {
+        let mut blocked = s2n_blocked_status::NOT_BLOCKED;
+        unsafe {
+            s2n_sendv_with_offset(self.connection.as_ptr(), bufs_ptr, count, 0, &mut blocked)
+                .into_poll()
+        }
+    }
+
     #[cfg(not(feature = "unstable-renegotiate"))]
     pub(crate) fn poll_recv_raw(
         &mut self,
@@ -731,6 +884,26 @@ impl Connection {
         unsafe { s2n_peek(self.connection.as_ptr()) as usize }
     }
 
+    /// Gets the number of bytes the connection has received "on the wire",
+    /// including TLS record headers, handshake messages, and encryption overhead.
+    ///
+    /// Always 0 once kTLS is enabled for receiving.
+    ///
+    /// Corresponds to [s2n_connection_get_wire_bytes_in].
+    pub fn wire_bytes_in(&self) -> u64 {
+        unsafe { s2n_connection_get_wire_bytes_in(self.connection.as_ptr()) }
+    }
+
+    /// Gets the number of bytes the connection has sent "on the wire",
+    /// including TLS record headers, handshake messages, and encryption overhead.
+    ///
+    /// Always 0 once kTLS is enabled for sending.
+    ///
+    /// Corresponds to [s2n_connection_get_wire_bytes_out].
+    pub fn wire_bytes_out(&self) -> u64 {
+        unsafe { s2n_connection_get_wire_bytes_out(self.connection.as_ptr()) }
+    }
+
     /// Attempts a graceful shutdown of the TLS connection.
     ///
     /// The shutdown is not complete until the necessary shutdown messages
@@ -843,6 +1016,69 @@ impl Connection {
         Ok(written.try_into().unwrap())
     }
 
+    /// The session id of the connection.
+    ///
+    /// The session id is always empty for TLS1.3 connections.
+    ///
+    /// Corresponds to [s2n_connection_get_session_id], but also
+    /// calls [s2n_connection_get_session_id_length].
+    pub fn session_id(&self) -> Result<Vec<u8>, Error> {
+        let len = unsafe {
+            s2n_connection_get_session_id_length(self.connection.as_ptr()).into_result()?
+        };
+        let mut session_id = vec![0; len as usize];
+        let written = unsafe {
+            s2n_connection_get_session_id(
+                self.connection.as_ptr(),
+                session_id.as_mut_ptr(),
+                session_id.len(),
+            )
+            .into_result()?
+        };
+        session_id.truncate(written as usize);
+        Ok(session_id)
+    }
+
+    /// How long the server indicated the most recently received session ticket
+    /// is valid for.
+    ///
+    /// Fails if no session ticket was received.
+    ///
+    /// Corresponds to [s2n_connection_get_session_ticket_lifetime_hint].
+    pub fn session_ticket_lifetime_hint(&self) -> Result<Duration, Error> {
+        let secs = unsafe {
+            s2n_connection_get_session_ticket_lifetime_hint(self.connection.as_ptr())
+                .into_result()?
+        };
+        Ok(Duration::from_secs(secs as u64))
+    }
+
+    /// Increases the number of session tickets the server sends after a TLS1.3 handshake.
+    ///
+    /// If the handshake is already complete, the new tickets are sent with
+    /// the next application data.
+    ///
+    /// Corresponds to [s2n_connection_add_new_tickets_to_send].
+    pub fn add_new_tickets_to_send(&mut self, count: u8) -> Result<&mut Self, Error> {
+        unsafe {
+            s2n_connection_add_new_tickets_to_send(self.connection.as_ptr(), count).into_result()
+        }?;
+        Ok(self)
+    }
+
+    /// The number of session tickets sent by the server.
+    ///
+    /// Only available for server connections.
+    ///
+    /// Corresponds to [s2n_connection_get_tickets_sent].
+    pub fn tickets_sent(&self) -> Result<u16, Error> {
+        let mut count = 0;
+        unsafe {
+            s2n_connection_get_tickets_sent(self.connection.as_ptr(), &mut count).into_result()
+        }?;
+        Ok(count)
+    }
+
     /// Sets a Waker on the connection context or clears it if `None` is passed.
     pub fn set_waker(&mut self, waker: Option<&Waker>) -> Result<&mut Self, Error> {
         let ctx = self.context_mut();
@@ -1023,6 +1259,26 @@ impl Connection {
         version.try_into()
     }
 
+    /// The highest protocol version supported by the client.
+    ///
+    /// Corresponds to [s2n_connection_get_client_protocol_version].
+    pub fn client_protocol_version(&self) -> Result<Version, Error> {
+        let version = unsafe {
+            s2n_connection_get_client_protocol_version(self.connection.as_ptr()).into_result()?
+        };
+        version.try_into()
+    }
+
+    /// The highest protocol version supported by the server.
+    ///
+    /// Corresponds to [s2n_connection_get_server_protocol_version].
+    pub fn server_protocol_version(&self) -> Result<Version, Error> {
+        let version = unsafe {
+            s2n_connection_get_server_protocol_version(self.connection.as_ptr()).into_result()?
+        };
+        version.try_into()
+    }
+
     /// Detects if the client hello is using the SSLv2 format.
     ///
     /// s2n-tls will not negotiate SSLv2, but will accept SSLv2 ClientHellos
@@ -1054,6 +1310,36 @@ impl Connection {
         }
     }
 
+    /// The [handshake_type](`Self::handshake_type()`), parsed into flags.
+    ///
+    /// Prefer this method over parsing the name: the version-specific flags
+    /// are checked against the negotiated protocol version.
+    ///
+    /// Corresponds to [s2n_connection_get_handshake_type_name].
+    pub fn handshake_flags(&self) -> Result<HandshakeType, Error> {
+        let flags = HandshakeType::from_name(self.handshake_type()?)?;
+        if flags.is_empty() {
+            return Ok(flags);
+        }
+        let version = self.actual_protocol_version()?;
+        Ok(flags.for_version(version))
+    }
+
+    /// The name of the last TLS message processed, like "SERVER_HELLO".
+    ///
+    /// Corresponds to [s2n_connection_get_last_message_name].
+    pub fn last_message_name(&self) -> Result<&str, Error> {
+        let message = unsafe {
+            s2n_connection_get_last_message_name(self.connection.as_ptr()).into_result()?
+        };
+        unsafe {
+            // SAFETY: The data is null terminated because it is declared as a C
+            //         string literal.
+            // SAFETY: message has a static lifetime because it lives in a static array.
+            const_str!(message)
+        }
+    }
+
     /// Corresponds to [s2n_connection_get_cipher].
     pub fn cipher_suite(&self) -> Result<&str, Error> {
         let cipher = unsafe { s2n_connection_get_cipher(self.connection.as_ptr()).into_result()? };
@@ -1066,6 +1352,28 @@ impl Connection {
         }
     }
 
+    /// The IANA value of the negotiated cipher suite, like `[0x13, 0x01]`
+    /// for TLS_AES_128_GCM_SHA256.
+    ///
+    /// Corresponds to [s2n_connection_get_cipher_iana_value].
+    pub fn cipher_suite_iana_value(&self) -> Result<[u8; 2], Error> {
+        let mut value = [0; 2];
+        let [first, second] = &mut value;
+        unsafe {
+            s2n_connection_get_cipher_iana_value(self.connection.as_ptr(), first, second)
+                .into_result()?;
+        }
+        Ok(value)
+    }
+
+    /// The negotiated cipher suite.
+    ///
+    /// Corresponds to [s2n_connection_get_cipher_iana_value].
+    pub fn selected_cipher_suite(&self) -> Result<CipherSuite, Error> {
+        let value = self.cipher_suite_iana_value()?;
+        Ok(u16::from_be_bytes(value).into())
+    }
+
     /// Corresponds to [s2n_connection_get_kem_name].
     #[deprecated = "PQ TLS 1.2 KEM Names are no longer supported. Use kem_group_name() to retrieve PQ TLS 1.3 Group name."]
     pub fn kem_name(&self) -> Option<&str> {
@@ -1157,6 +1465,18 @@ impl Connection {
         }
     }
 
+    /// The negotiated key exchange group, or `None` if the key exchange didn't use one,
+    /// for example because the connection used RSA key exchange.
+    ///
+    /// A group without a [`NamedGroup`] variant is reported as
+    /// `NamedGroup::Unknown(0)`, because s2n-tls only reports the group's name.
+    ///
+    /// Corresponds to [s2n_connection_get_key_exchange_group].
+    pub fn selected_named_group(&self) -> Option<NamedGroup> {
+        self.selected_key_exchange_group()
+            .map(NamedGroup::from_name)
+    }
+
     /// Corresponds to [s2n_connection_get_selected_signature_algorithm].
     pub fn selected_signature_algorithm(&self) -> Result<SignatureAlgorithm, Error> {
         let mut sig_alg = s2n_tls_signature_algorithm::ANONYMOUS;
@@ -1219,6 +1539,30 @@ impl Connection {
         })
     }
 
+    /// The signature scheme used by the server to sign the handshake.
+    ///
+    /// Returns `None` if the handshake wasn't signed, for example
+    /// because the connection was resumed.
+    ///
+    /// Corresponds to [s2n_connection_get_signature_scheme].
+    pub fn signature_scheme(&self) -> Result<Option<SignatureScheme>, Error> {
+        let mut name = core::ptr::null();
+        unsafe {
+            s2n_connection_get_signature_scheme(self.connection.as_ptr(), &mut name)
+                .into_result()?;
+        }
+        let name = unsafe {
+            // SAFETY: The data is null terminated because it is declared as a C
+            //         string literal.
+            // SAFETY: name has a static lifetime because it lives on s2n_signature_scheme,
+            //         a static struct.
+            const_str!(name)?
+        };
+        SignatureScheme::from_name(name)
+    }
+
+    /// The application protocol negotiated with ALPN, or with NPN if enabled.
+    ///
     /// Corresponds to [s2n_get_application_protocol].
     pub fn application_protocol(&self) -> Option<&[u8]> {
         let protocol = unsafe { s2n_get_application_protocol(self.connection.as_ptr()) };
@@ -1228,6 +1572,46 @@ impl Connection {
         Some(unsafe { CStr::from_ptr(protocol).to_bytes() })
     }
 
+    /// The signed certificate timestamp (SCT) list sent by the server.
+    ///
+    /// Only available if the client requested SCTs with
+    /// [config::Builder::set_ct_support_level](`crate::config::Builder::set_ct_support_level()`).
+    /// The list can be decoded with
+    /// [SignedCertificateTimestamp::parse_list](`crate::sct::SignedCertificateTimestamp::parse_list()`).
+    ///
+    /// Corresponds to [s2n_connection_get_sct_list].
+    pub fn sct_list(&self) -> Option<&[u8]> {
+        let mut length = 0;
+        let data = unsafe { s2n_connection_get_sct_list(self.connection.as_ptr(), &mut length) };
+        if data.is_null() || length == 0 {
+            return None;
+        }
+        Some(unsafe { core::slice::from_raw_parts(data, length as usize) })
+    }
+
+    /// The OCSP response stapled by the server.
+    ///
+    /// Only available if the client requested OCSP stapling with
+    /// [config::Builder::enable_ocsp](`crate::config::Builder::enable_ocsp()`).
+    ///
+    /// Corresponds to [s2n_connection_get_ocsp_response].
+    pub fn ocsp_response(&self) -> Option<&[u8]> {
+        let mut length = 0;
+        let data =
+            unsafe { s2n_connection_get_ocsp_response(self.connection.as_ptr(), &mut length) };
+        if data.is_null() || length == 0 {
+            return None;
+        }
+        Some(unsafe { core::slice::from_raw_parts(data, length as usize) })
+    }
+
+    /// Returns true if the server stapled an OCSP response to its certificate.
+    ///
+    /// Corresponds to [s2n_connection_is_ocsp_stapled].
+    pub fn is_ocsp_stapled(&self) -> bool {
+        unsafe { s2n_connection_is_ocsp_stapled(self.connection.as_ptr()) == 1 }
+    }
+
     /// Provides access to the TLS-Exporter functionality.
     ///
     /// See https://datatracker.ietf.org/doc/html/rfc5705 and https://www.rfc-editor.org/rfc/rfc8446.
@@ -1408,6 +1792,100 @@ impl Connection {
         Ok(())
     }
 
+    /// Sets which type of PSKs the connection uses.
+    ///
+    /// Overrides [config::Builder::set_psk_mode](`crate::config::Builder::set_psk_mode()`).
+    /// Fails if PSKs of a different type were already added to the connection.
+    ///
+    /// Corresponds to [s2n_connection_set_psk_mode].
+    pub fn set_psk_mode(&mut self, mode: PskMode) -> Result<&mut Self, Error> {
+        unsafe {
+            s2n_connection_set_psk_mode(self.connection.as_ptr(), mode.into()).into_result()
+        }?;
+        Ok(self)
+    }
+
+    /// Reports whether the negotiated PSK was an external PSK or was established
+    /// by session resumption.
+    ///
+    /// Returns `None` if no PSK was negotiated. TLS1.2 session resumption does
+    /// not use PSKs, so also returns `None`.
+    pub fn negotiated_psk_mode(&self) -> Result<Option<PskMode>, Error> {
+        if self.negotiated_psk_identity_length()? == 0 {
+            Ok(None)
+        } else if self.resumed() {
+            Ok(Some(PskMode::Resumption))
+        } else {
+            Ok(Some(PskMode::External))
+        }
+    }
+
+    /// Sets the maximum bytes of early data the server will accept.
+    ///
+    /// Overrides [config::Builder::set_server_max_early_data_size](`crate::config::Builder::set_server_max_early_data_size()`).
+    ///
+    /// Corresponds to [s2n_connection_set_server_max_early_data_size].
+    pub fn set_server_max_early_data_size(&mut self, size: u32) -> Result<&mut Self, Error> {
+        unsafe {
+            s2n_connection_set_server_max_early_data_size(self.connection.as_ptr(), size)
+                .into_result()
+        }?;
+        Ok(self)
+    }
+
+    /// Sets the early data context that the server includes in new session tickets.
+    ///
+    /// The context is available to the [`EarlyDataCallback`] when the ticket is
+    /// later used for early data.
+    ///
+    /// Corresponds to [s2n_connection_set_server_early_data_context].
+    pub fn set_server_early_data_context(&mut self, context: &[u8]) -> Result<&mut Self, Error> {
+        let context_len = context.len().try_into().map_err(|_| Error::INVALID_INPUT)?;
+        unsafe {
+            s2n_connection_set_server_early_data_context(
+                self.connection.as_ptr(),
+                context.as_ptr(),
+                context_len,
+            )
+            .into_result()
+        }?;
+        Ok(self)
+    }
+
+    /// Corresponds to [s2n_connection_get_early_data_status].
+    pub fn early_data_status(&self) -> Result<EarlyDataStatus, Error> {
+        let mut status = s2n_early_data_status_t::NOT_REQUESTED;
+        unsafe {
+            s2n_connection_get_early_data_status(self.connection.as_ptr(), &mut status)
+                .into_result()
+        }?;
+        status.try_into()
+    }
+
+    /// The early data that can still be sent or received on the connection.
+    ///
+    /// Corresponds to [s2n_connection_get_remaining_early_data_size].
+    pub fn remaining_early_data_size(&self) -> Result<usize, Error> {
+        let mut size = 0;
+        unsafe {
+            s2n_connection_get_remaining_early_data_size(self.connection.as_ptr(), &mut size)
+                .into_result()
+        }?;
+        Ok(size as usize)
+    }
+
+    /// The maximum early data that can ever be sent or received on the connection.
+    ///
+    /// Corresponds to [s2n_connection_get_max_early_data_size].
+    pub fn max_early_data_size(&self) -> Result<usize, Error> {
+        let mut size = 0;
+        unsafe {
+            s2n_connection_get_max_early_data_size(self.connection.as_ptr(), &mut size)
+                .into_result()
+        }?;
+        Ok(size as usize)
+    }
+
     /// Associates an arbitrary application context with the Connection to be later retrieved via
     /// the [`Self::application_context()`] and [`Self::application_context_mut()`] APIs.
     ///
@@ -1458,6 +1936,11 @@ impl Connection {
         &mut self.context_mut().cert_request_state
     }
 
+    #[cfg(feature = "unstable-crl")]
+    pub(crate) fn crl_state(&mut self) -> &mut CrlState {
+        &mut self.context_mut().crl_state
+    }
+
     #[cfg(feature = "unstable-renegotiate")]
     pub(crate) fn renegotiate_state_mut(&mut self) -> &mut RenegotiateState {
         &mut self.context_mut().renegotiate_state
@@ -1480,6 +1963,8 @@ struct Context {
     pub(crate) renegotiate_state: RenegotiateState,
     #[cfg(feature = "unstable-cert_authorities")]
     pub(crate) cert_request_state: CertRequestState,
+    #[cfg(feature = "unstable-crl")]
+    pub(crate) crl_state: CrlState,
 }
 
 impl Context {
@@ -1495,6 +1980,8 @@ impl Context {
             renegotiate_state: RenegotiateState::default(),
             #[cfg(feature = "unstable-cert_authorities")]
             cert_request_state: CertRequestState::default(),
+            #[cfg(feature = "unstable-crl")]
+            crl_state: CrlState::default(),
         }
     }
 }
@@ -1735,6 +2222,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn send_vectored() -> Result<(), Box<dyn std::error::Error>> {
+        let config = build_config(&security::DEFAULT_TLS13)?;
+        let mut pair = TestPair::from_config(&config);
+        pair.handshake()?;
+
+        let bufs = [
+            IoSlice::new(b"header"),
+            IoSlice::new(&[]),
+            IoSlice::new(b"body"),
+        ];
+        assert!(matches!(
+            pair.client.poll_send_vectored(&bufs),
+            Poll::Ready(Ok(10))
+        ));
+
+        let mut received = [0; 10];
+        assert!(matches!(
+            pair.server.poll_recv(&mut received),
+            Poll::Ready(Ok(10))
+        ));
+        assert_eq!(&received, b"headerbody");
+        Ok(())
+    }
+
     /// Test that `unstable_as_ptr()` can be used to call an s2n_tls_sys API.
     #[cfg(s2n_tls_external_build)]
     #[test]
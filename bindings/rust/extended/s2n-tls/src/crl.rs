@@ -0,0 +1,547 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Certificate revocation checking with Certificate Revocation Lists (CRLs).
+//!
+//! See the [CRL Validation](https://github.com/aws/s2n-tls/blob/main/docs/usage-guide/topics/ch11-crl.md)
+//! section of the usage guide for the primary documentation of the feature.
+//!
+//! This is an unstable s2n API.
+
+use s2n_tls_sys::*;
+
+use crate::{
+    callbacks::{with_context, AsyncCallback, ConnectionFuture, ConnectionFutureResult},
+    config,
+    connection::Connection,
+    enums::CallbackResult,
+    error::{Error, Fallible},
+};
+use core::task::Poll;
+use std::{
+    cell::Cell,
+    pin::Pin,
+    ptr::NonNull,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+/// A Certificate Revocation List.
+///
+/// Provided to s2n-tls from a [`CrlLookupCallback`].
+pub struct Crl {
+    // SAFETY: `ptr.as_ptr()` allows a `*mut s2n_crl` to be returned from `&Crl`.
+    // This is required because all s2n-tls C crl APIs take a mutable pointer.
+    // This is only safe if the `*mut s2n_crl` from `&Crl` is still treated as
+    // logically const by the s2n-tls C library.
+    ptr: NonNull<s2n_crl>,
+}
+
+/// # Safety
+///
+/// Safety: Crl objects can be sent across threads
+unsafe impl Send for Crl {}
+
+/// # Safety
+///
+/// Safety: There are no methods that mutate the Crl through a shared reference
+/// (i.e., no interior mutability is exposed)
+unsafe impl Sync for Crl {}
+
+impl Crl {
+    /// Load a CRL from PEM data.
+    ///
+    /// Corresponds to [s2n_crl_new] and [s2n_crl_load_pem].
+    pub fn from_pem(pem: &[u8]) -> Result<Self, Error> {
+        crate::init::init();
+        let ptr = unsafe { s2n_crl_new().into_result() }?;
+        let crl = Self { ptr };
+        unsafe {
+            // s2n_crl_load_pem does not modify the pem data
+            s2n_crl_load_pem(crl.as_ptr(), pem.as_ptr() as *mut u8, pem.len()).into_result()
+        }?;
+        Ok(crl)
+    }
+
+    /// The hash of the CRL's issuer.
+    ///
+    /// Use to match the CRL to the certificate in a [`CrlLookup`],
+    /// which provides the hash of the certificate's issuer.
+    ///
+    /// Corresponds to [s2n_crl_get_issuer_hash].
+    pub fn issuer_hash(&self) -> Result<u64, Error> {
+        let mut hash = 0;
+        unsafe { s2n_crl_get_issuer_hash(self.as_ptr(), &mut hash).into_result() }?;
+        Ok(hash)
+    }
+
+    /// Fails if the CRL's thisUpdate field is in the future,
+    /// or if the status can't be determined.
+    ///
+    /// Corresponds to [s2n_crl_validate_active].
+    pub fn validate_active(&self) -> Result<(), Error> {
+        unsafe { s2n_crl_validate_active(self.as_ptr()).into_result() }?;
+        Ok(())
+    }
+
+    /// Fails if the CRL's nextUpdate field is in the past,
+    /// or if the status can't be determined.
+    ///
+    /// Corresponds to [s2n_crl_validate_not_expired].
+    pub fn validate_not_expired(&self) -> Result<(), Error> {
+        unsafe { s2n_crl_validate_not_expired(self.as_ptr()).into_result() }?;
+        Ok(())
+    }
+
+    fn as_ptr(&self) -> *mut s2n_crl {
+        self.ptr.as_ptr()
+    }
+}
+
+impl Drop for Crl {
+    /// Corresponds to [s2n_crl_free].
+    fn drop(&mut self) {
+        // ignore failures. There isn't anything to be done to handle them, but
+        // allowing the program to continue is preferable to crashing.
+        let _ = unsafe { s2n_crl_free(&mut self.ptr.as_ptr()).into_result() };
+    }
+}
+
+/// CRLs provided to s2n-tls, which must be kept alive until the
+/// peer's certificate chain is validated.
+type ProvidedCrls = Arc<Mutex<Vec<Crl>>>;
+
+#[derive(Default)]
+pub(crate) struct CrlState {
+    crls: ProvidedCrls,
+    /// Lookups that didn't complete during the current call into s2n-tls.
+    pending: Vec<Pin<Box<dyn ConnectionFuture>>>,
+    error: Option<Error>,
+}
+
+/// A request for the CRL of a certificate received from the peer.
+///
+/// Either [`Self::set()`] or [`Self::ignore()`] must be called
+/// before the handshake can continue.
+pub struct CrlLookup {
+    raw: NonNull<s2n_crl_lookup>,
+    crls: ProvidedCrls,
+}
+
+/// # Safety
+///
+/// Safety: s2n_crl_lookup objects can be sent across threads
+unsafe impl Send for CrlLookup {}
+
+/// # Safety
+///
+/// Safety: All C methods that mutate the s2n_crl_lookup are wrapped
+/// in Rust methods that require ownership.
+unsafe impl Sync for CrlLookup {}
+
+impl CrlLookup {
+    fn from_cb(lookup: *mut s2n_crl_lookup, crls: ProvidedCrls) -> Result<Self, Error> {
+        let raw = NonNull::new(lookup).ok_or(Error::INVALID_INPUT)?;
+        Ok(Self { raw, crls })
+    }
+
+    /// The hash of the certificate's issuer.
+    ///
+    /// Matches [`Crl::issuer_hash()`] for the CRL published by the issuer.
+    ///
+    /// Corresponds to [s2n_crl_lookup_get_cert_issuer_hash].
+    pub fn cert_issuer_hash(&self) -> Result<u64, Error> {
+        let mut hash = 0;
+        unsafe { s2n_crl_lookup_get_cert_issuer_hash(self.raw.as_ptr(), &mut hash).into_result() }?;
+        Ok(hash)
+    }
+
+    /// Provide the CRL to use when validating the certificate.
+    ///
+    /// Corresponds to [s2n_crl_lookup_set].
+    pub fn set(self, crl: Crl) -> Result<(), Error> {
+        unsafe { s2n_crl_lookup_set(self.raw.as_ptr(), crl.as_ptr()).into_result() }?;
+        self.crls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(crl);
+        Ok(())
+    }
+
+    /// Skip providing a CRL for the certificate.
+    ///
+    /// Validation will fail if the certificate is part of the chain of trust.
+    ///
+    /// Corresponds to [s2n_crl_lookup_ignore].
+    pub fn ignore(self) -> Result<(), Error> {
+        unsafe { s2n_crl_lookup_ignore(self.raw.as_ptr()).into_result() }?;
+        Ok(())
+    }
+}
+
+/// A trait for the callback used to provide CRLs for received certificates.
+///
+/// The callback is triggered once for each certificate received from the peer.
+///
+/// Use in conjunction with [`config::Builder::set_crl_lookup_callback()`].
+pub trait CrlLookupCallback: 'static + Send + Sync {
+    /// The application can call [`CrlLookup::set()`] or [`CrlLookup::ignore()`]
+    /// and return `Ok(None)` to resolve the callback synchronously, or return
+    /// an `Ok(Some(ConnectionFuture))` which resolves `lookup` later.
+    fn on_crl_lookup(
+        &self,
+        connection: &mut Connection,
+        lookup: CrlLookup,
+    ) -> ConnectionFutureResult;
+}
+
+thread_local! {
+    /// The connection currently calling into s2n-tls on this thread.
+    ///
+    /// Unlike other callbacks, the C CRL lookup callback isn't given a connection.
+    static CURRENT_CONNECTION: Cell<*mut s2n_connection> =
+        const { Cell::new(core::ptr::null_mut()) };
+}
+
+/// Polls all the CRL lookups that couldn't complete synchronously.
+struct PendingLookups(Vec<Pin<Box<dyn ConnectionFuture>>>);
+
+impl ConnectionFuture for PendingLookups {
+    fn poll(
+        mut self: Pin<&mut Self>,
+        connection: &mut Connection,
+        ctx: &mut core::task::Context,
+    ) -> Poll<Result<(), Error>> {
+        let lookups = &mut self.0;
+        let mut index = 0;
+        while index < lookups.len() {
+            match lookups[index].as_mut().poll(connection, ctx) {
+                Poll::Ready(Ok(())) => {
+                    lookups.swap_remove(index);
+                }
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => index += 1,
+            }
+        }
+        if lookups.is_empty() {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl Connection {
+    /// Calls into s2n-tls, with this connection made available to the CRL lookup callback.
+    ///
+    /// Any lookups that didn't complete are combined into a single async callback.
+    pub(crate) fn with_crl_lookups<F, T>(&mut self, action: F) -> Poll<Result<T, Error>>
+    where
+        F: FnOnce(&mut Connection) -> Poll<Result<T, Error>>,
+    {
+        // Restore the previous connection even if `action` panics.
+        struct Restore(*mut s2n_connection);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT_CONNECTION.with(|current| current.set(self.0));
+            }
+        }
+
+        let restore = Restore(CURRENT_CONNECTION.with(|current| current.replace(self.as_ptr())));
+        let poll = action(self);
+        drop(restore);
+
+        let state = self.crl_state();
+        let pending = core::mem::take(&mut state.pending);
+        let error = state.error.take();
+        if poll.is_pending() {
+            if let Some(error) = error {
+                AsyncCallback::trigger(Err(error), self);
+            } else if !pending.is_empty() {
+                AsyncCallback::trigger(Ok(Some(Box::pin(PendingLookups(pending)))), self);
+            }
+        }
+        poll
+    }
+}
+
+impl config::Builder {
+    /// Set a callback to provide CRLs for the certificates received from the peer.
+    ///
+    /// Corresponds to [s2n_config_set_crl_lookup_cb].
+    pub fn set_crl_lookup_callback<T: CrlLookupCallback>(
+        &mut self,
+        handler: T,
+    ) -> Result<&mut Self, Error> {
+        unsafe extern "C" fn crl_lookup_cb(
+            lookup: *mut s2n_crl_lookup,
+            _context: *mut libc::c_void,
+        ) -> libc::c_int {
+            let conn_ptr = CURRENT_CONNECTION.with(|current| current.get());
+            if conn_ptr.is_null() {
+                return CallbackResult::Failure.into();
+            }
+            with_context(conn_ptr, |conn, context| {
+                let callback = match context.crl_lookup_callback.as_ref() {
+                    Some(callback) => callback,
+                    None => return CallbackResult::Failure,
+                };
+                let crls = conn.crl_state().crls.clone();
+                let result = CrlLookup::from_cb(lookup, crls)
+                    .and_then(|lookup| callback.on_crl_lookup(conn, lookup));
+                // Errors are reported once the handshake blocks on the lookups,
+                // since s2n-tls would otherwise replace them with a generic error.
+                let state = conn.crl_state();
+                match result {
+                    Ok(Some(future)) => state.pending.push(future),
+                    Ok(None) => {}
+                    Err(error) => {
+                        state.error.get_or_insert(error);
+                    }
+                }
+                CallbackResult::Success
+            })
+            .into()
+        }
+
+        let context = unsafe {
+            // SAFETY: usage of context_mut is safe in the builder, because while
+            // it is being built, the Builder is the only reference to the config.
+            self.config.context_mut()
+        };
+        context.crl_lookup_callback = Some(Box::new(handler));
+        unsafe {
+            s2n_config_set_crl_lookup_cb(
+                self.as_mut_ptr(),
+                Some(crl_lookup_cb),
+                core::ptr::null_mut(),
+            )
+            .into_result()
+        }?;
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::Config,
+        security,
+        testing::{
+            assert_test_error, test_error, Counter, InsecureAcceptAllCertificatesHandler, TestPair,
+        },
+    };
+    use std::collections::HashMap;
+
+    const CRL_PEMS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../../../tests/pems/crl/");
+
+    fn read_pem(name: &str) -> Vec<u8> {
+        let path = format!("{CRL_PEMS_PATH}{name}.pem");
+        std::fs::read(&path).unwrap_or_else(|_| panic!("Failed to read pem at {path}"))
+    }
+
+    fn load_crl(name: &str) -> Crl {
+        Crl::from_pem(&read_pem(name)).unwrap()
+    }
+
+    /// Provides the test CRLs, indexed by issuer hash.
+    #[derive(Default)]
+    struct CrlStore(HashMap<u64, Vec<u8>>);
+
+    impl CrlStore {
+        fn new() -> Self {
+            let mut store = Self::default();
+            for name in ["root_crl", "intermediate_crl", "intermediate_revoked_crl"] {
+                let pem = read_pem(name);
+                let hash = Crl::from_pem(&pem).unwrap().issuer_hash().unwrap();
+                store.0.insert(hash, pem);
+            }
+            store
+        }
+
+        fn lookup(&self, lookup: CrlLookup) -> Result<(), Error> {
+            match self.0.get(&lookup.cert_issuer_hash()?) {
+                Some(pem) => lookup.set(Crl::from_pem(pem)?),
+                None => lookup.ignore(),
+            }
+        }
+    }
+
+    fn crl_pair<T: CrlLookupCallback>(chain: &str, callback: T) -> Result<TestPair, Error> {
+        let server_config = {
+            let mut config = Config::builder();
+            config.set_security_policy(&security::DEFAULT_TLS13)?;
+            config.load_pem(
+                &read_pem(&format!("{chain}_cert_chain")),
+                &read_pem(&format!("{chain}_key")),
+            )?;
+            config.build()?
+        };
+        let client_config = {
+            let mut config = Config::builder();
+            config.set_security_policy(&security::DEFAULT_TLS13)?;
+            config.trust_pem(&read_pem("root_cert"))?;
+            config.set_verify_host_callback(InsecureAcceptAllCertificatesHandler {})?;
+            config.set_crl_lookup_callback(callback)?;
+            config.build()?
+        };
+        Ok(TestPair::from_configs(&client_config, &server_config))
+    }
+
+    struct SyncCallback(CrlStore, Counter);
+    impl CrlLookupCallback for SyncCallback {
+        fn on_crl_lookup(
+            &self,
+            _conn: &mut Connection,
+            lookup: CrlLookup,
+        ) -> ConnectionFutureResult {
+            self.1.increment();
+            self.0.lookup(lookup)?;
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn crl_validation() -> Result<(), Error> {
+        let root = load_crl("root_crl");
+        let intermediate = load_crl("intermediate_crl");
+        assert_ne!(root.issuer_hash()?, intermediate.issuer_hash()?);
+        for crl in [root, intermediate] {
+            crl.validate_active()?;
+            crl.validate_not_expired()?;
+        }
+
+        let not_active = load_crl("intermediate_invalid_this_update_crl");
+        assert!(not_active.validate_active().is_err());
+        not_active.validate_not_expired()?;
+
+        let expired = load_crl("intermediate_invalid_next_update_crl");
+        expired.validate_active()?;
+        assert!(expired.validate_not_expired().is_err());
+
+        assert!(Crl::from_pem(b"not a crl").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn sync_lookup() -> Result<(), Error> {
+        let counter = Counter::default();
+        let mut pair = crl_pair(
+            "none_revoked",
+            SyncCallback(CrlStore::new(), counter.clone()),
+        )?;
+        pair.handshake()?;
+        // Called for both the leaf and the intermediate certificate
+        assert_eq!(counter.count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn revoked_cert() -> Result<(), Error> {
+        for chain in ["leaf_revoked", "intermediate_revoked"] {
+            let callback = SyncCallback(CrlStore::new(), Counter::default());
+            let mut pair = crl_pair(chain, callback)?;
+            let error = pair.handshake().unwrap_err();
+            assert_eq!(error.name(), "S2N_ERR_CERT_REVOKED");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn ignored_lookup() -> Result<(), Error> {
+        struct IgnoreCallback;
+        impl CrlLookupCallback for IgnoreCallback {
+            fn on_crl_lookup(
+                &self,
+                _conn: &mut Connection,
+                lookup: CrlLookup,
+            ) -> ConnectionFutureResult {
+                lookup.ignore()?;
+                Ok(None)
+            }
+        }
+
+        let mut pair = crl_pair("none_revoked", IgnoreCallback)?;
+        let error = pair.handshake().unwrap_err();
+        assert_eq!(error.name(), "S2N_ERR_CRL_LOOKUP_FAILED");
+        Ok(())
+    }
+
+    #[test]
+    fn async_lookup() -> Result<(), Error> {
+        const POLL_COUNT: usize = 10;
+
+        struct LookupFuture {
+            counter: usize,
+            store: Arc<CrlStore>,
+            lookup: Option<CrlLookup>,
+        }
+        impl ConnectionFuture for LookupFuture {
+            fn poll(
+                mut self: Pin<&mut Self>,
+                _conn: &mut Connection,
+                ctx: &mut core::task::Context,
+            ) -> Poll<Result<(), Error>> {
+                ctx.waker().wake_by_ref();
+                self.counter += 1;
+                if self.counter < POLL_COUNT {
+                    return Poll::Pending;
+                }
+                let lookup = self.lookup.take().expect("polled after completion");
+                Poll::Ready(self.store.lookup(lookup))
+            }
+        }
+
+        struct AsyncLookupCallback(Arc<CrlStore>, Counter);
+        impl CrlLookupCallback for AsyncLookupCallback {
+            fn on_crl_lookup(
+                &self,
+                _conn: &mut Connection,
+                lookup: CrlLookup,
+            ) -> ConnectionFutureResult {
+                self.1.increment();
+                let future = LookupFuture {
+                    counter: 0,
+                    store: self.0.clone(),
+                    lookup: Some(lookup),
+                };
+                Ok(Some(Box::pin(future)))
+            }
+        }
+
+        let counter = Counter::default();
+        let callback = AsyncLookupCallback(Arc::new(CrlStore::new()), counter.clone());
+        let mut pair = crl_pair("none_revoked", callback)?;
+        pair.handshake()?;
+        assert_eq!(counter.count(), 2);
+
+        let mut pair = crl_pair(
+            "leaf_revoked",
+            AsyncLookupCallback(Arc::new(CrlStore::new()), Counter::default()),
+        )?;
+        let error = pair.handshake().unwrap_err();
+        assert_eq!(error.name(), "S2N_ERR_CERT_REVOKED");
+        Ok(())
+    }
+
+    #[test]
+    fn callback_error() -> Result<(), Error> {
+        const ERROR: &str = "crl lookup callback error";
+
+        struct ErrorCallback;
+        impl CrlLookupCallback for ErrorCallback {
+            fn on_crl_lookup(
+                &self,
+                _conn: &mut Connection,
+                _lookup: CrlLookup,
+            ) -> ConnectionFutureResult {
+                Err(test_error(ERROR))
+            }
+        }
+
+        let mut pair = crl_pair("none_revoked", ErrorCallback)?;
+        let error = pair.handshake().unwrap_err();
+        assert_test_error(error, ERROR);
+        Ok(())
+    }
+}
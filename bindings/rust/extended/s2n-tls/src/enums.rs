@@ -4,7 +4,7 @@
 #![allow(clippy::missing_safety_doc)] // TODO add safety docs
 
 use crate::error::Error;
-use core::convert::TryFrom;
+use core::{convert::TryFrom, fmt, ops, task::Poll};
 use s2n_tls_sys::*;
 
 #[derive(Debug, PartialEq, Copy, Clone)]
@@ -22,6 +22,18 @@ impl From<CallbackResult> for s2n_status_code::Type {
     }
 }
 
+impl CallbackResult {
+    /// Converts the result of a callback that may block into an s2n status code.
+    ///
+    /// `Poll::Pending` corresponds to S2N_CALLBACK_BLOCKED.
+    pub(crate) fn poll_into(poll: Poll<CallbackResult>) -> s2n_status_code::Type {
+        match poll {
+            Poll::Ready(result) => result.into(),
+            Poll::Pending => s2n_status_code::CALLBACK_BLOCKED,
+        }
+    }
+}
+
 impl<T, E> From<Result<T, E>> for CallbackResult {
     fn from(result: Result<T, E>) -> CallbackResult {
         match result {
@@ -163,6 +175,87 @@ impl From<ClientAuthType> for s2n_cert_auth_type::Type {
     }
 }
 
+impl TryFrom<s2n_cert_auth_type::Type> for ClientAuthType {
+    type Error = Error;
+
+    fn try_from(input: s2n_cert_auth_type::Type) -> Result<Self, Self::Error> {
+        let auth_type = match input {
+            s2n_cert_auth_type::REQUIRED => Self::Required,
+            s2n_cert_auth_type::OPTIONAL => Self::Optional,
+            s2n_cert_auth_type::NONE => Self::None,
+            _ => return Err(Error::INVALID_INPUT),
+        };
+        Ok(auth_type)
+    }
+}
+
+/// Corresponds to [s2n_ct_support_level].
+#[non_exhaustive]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum CtSupportLevel {
+    None,
+    Request,
+}
+
+impl From<CtSupportLevel> for s2n_ct_support_level::Type {
+    fn from(input: CtSupportLevel) -> s2n_ct_support_level::Type {
+        match input {
+            CtSupportLevel::None => s2n_ct_support_level::NONE,
+            CtSupportLevel::Request => s2n_ct_support_level::REQUEST,
+        }
+    }
+}
+
+/// Maximum fragment lengths from [RFC 6066](https://www.rfc-editor.org/rfc/rfc6066#section-4).
+///
+/// Corresponds to [s2n_max_frag_len].
+#[non_exhaustive]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum MaxFragmentLength {
+    Len512,
+    Len1024,
+    Len2048,
+    Len4096,
+}
+
+impl MaxFragmentLength {
+    /// The maximum record fragment size, in bytes.
+    pub fn size(&self) -> usize {
+        match self {
+            MaxFragmentLength::Len512 => 512,
+            MaxFragmentLength::Len1024 => 1024,
+            MaxFragmentLength::Len2048 => 2048,
+            MaxFragmentLength::Len4096 => 4096,
+        }
+    }
+}
+
+impl From<MaxFragmentLength> for s2n_max_frag_len::Type {
+    fn from(input: MaxFragmentLength) -> s2n_max_frag_len::Type {
+        match input {
+            MaxFragmentLength::Len512 => s2n_max_frag_len::LEN_512,
+            MaxFragmentLength::Len1024 => s2n_max_frag_len::LEN_1024,
+            MaxFragmentLength::Len2048 => s2n_max_frag_len::LEN_2048,
+            MaxFragmentLength::Len4096 => s2n_max_frag_len::LEN_4096,
+        }
+    }
+}
+
+impl TryFrom<s2n_max_frag_len::Type> for MaxFragmentLength {
+    type Error = Error;
+
+    fn try_from(input: s2n_max_frag_len::Type) -> Result<Self, Self::Error> {
+        let length = match input {
+            s2n_max_frag_len::LEN_512 => Self::Len512,
+            s2n_max_frag_len::LEN_1024 => Self::Len1024,
+            s2n_max_frag_len::LEN_2048 => Self::Len2048,
+            s2n_max_frag_len::LEN_4096 => Self::Len4096,
+            _ => return Err(Error::INVALID_INPUT),
+        };
+        Ok(length)
+    }
+}
+
 /// Corresponds to [s2n_alert_behavior].
 #[non_exhaustive]
 #[derive(Debug, PartialEq, Copy, Clone)]
@@ -238,6 +331,571 @@ impl TryFrom<s2n_tls_hash_algorithm::Type> for HashAlgorithm {
     }
 }
 
+/// A TLS signature scheme, as named in the
+/// [IANA registry](https://www.iana.org/assignments/tls-parameters/tls-parameters.xhtml#tls-signaturescheme).
+///
+/// TLS1.2 ECDSA schemes don't require a specific curve, so are distinct from
+/// the TLS1.3 schemes with the same IANA value. For example, ECDSA_SHA256 and
+/// ECDSA_SECP256R1_SHA256 are both 0x0403.
+#[non_exhaustive]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[allow(non_camel_case_types)]
+pub enum SignatureScheme {
+    /// The TLS1.1 and earlier RSA signature, which has no IANA value.
+    RSA_PKCS1_MD5_SHA1,
+    RSA_PKCS1_SHA1,
+    RSA_PKCS1_SHA224,
+    RSA_PKCS1_SHA256,
+    RSA_PKCS1_SHA384,
+    RSA_PKCS1_SHA512,
+    ECDSA_SHA1,
+    ECDSA_SHA224,
+    ECDSA_SHA256,
+    ECDSA_SHA384,
+    ECDSA_SHA512,
+    ECDSA_SECP256R1_SHA256,
+    ECDSA_SECP384R1_SHA384,
+    ECDSA_SECP521R1_SHA512,
+    RSA_PSS_RSAE_SHA256,
+    RSA_PSS_RSAE_SHA384,
+    RSA_PSS_RSAE_SHA512,
+    RSA_PSS_PSS_SHA256,
+    RSA_PSS_PSS_SHA384,
+    RSA_PSS_PSS_SHA512,
+    MLDSA44,
+    MLDSA65,
+    MLDSA87,
+}
+
+impl SignatureScheme {
+    /// Maps the names returned by [s2n_connection_get_signature_scheme].
+    ///
+    /// Returns `Ok(None)` for "none", which indicates that no scheme was negotiated.
+    pub(crate) fn from_name(name: &str) -> Result<Option<Self>, Error> {
+        let scheme = match name {
+            "none" => return Ok(None),
+            "legacy_rsa_md5_sha1" => Self::RSA_PKCS1_MD5_SHA1,
+            "rsa_pkcs1_sha1" => Self::RSA_PKCS1_SHA1,
+            "legacy_rsa_sha224" => Self::RSA_PKCS1_SHA224,
+            "rsa_pkcs1_sha256" => Self::RSA_PKCS1_SHA256,
+            "rsa_pkcs1_sha384" => Self::RSA_PKCS1_SHA384,
+            "rsa_pkcs1_sha512" => Self::RSA_PKCS1_SHA512,
+            "ecdsa_sha1" => Self::ECDSA_SHA1,
+            "legacy_ecdsa_sha224" => Self::ECDSA_SHA224,
+            "ecdsa_sha256" | "legacy_ecdsa_sha256" => Self::ECDSA_SHA256,
+            "ecdsa_sha384" | "legacy_ecdsa_sha384" => Self::ECDSA_SHA384,
+            "ecdsa_sha512" | "legacy_ecdsa_sha512" => Self::ECDSA_SHA512,
+            "ecdsa_secp256r1_sha256" => Self::ECDSA_SECP256R1_SHA256,
+            "ecdsa_secp384r1_sha384" => Self::ECDSA_SECP384R1_SHA384,
+            "ecdsa_secp521r1_sha512" => Self::ECDSA_SECP521R1_SHA512,
+            "rsa_pss_rsae_sha256" => Self::RSA_PSS_RSAE_SHA256,
+            "rsa_pss_rsae_sha384" => Self::RSA_PSS_RSAE_SHA384,
+            "rsa_pss_rsae_sha512" => Self::RSA_PSS_RSAE_SHA512,
+            "rsa_pss_pss_sha256" => Self::RSA_PSS_PSS_SHA256,
+            "rsa_pss_pss_sha384" => Self::RSA_PSS_PSS_SHA384,
+            "rsa_pss_pss_sha512" => Self::RSA_PSS_PSS_SHA512,
+            "mldsa44" => Self::MLDSA44,
+            "mldsa65" => Self::MLDSA65,
+            "mldsa87" => Self::MLDSA87,
+            _ => return Err(Error::INVALID_INPUT),
+        };
+        Ok(Some(scheme))
+    }
+
+    /// The IANA value of the signature scheme.
+    ///
+    /// Returns `None` for RSA_PKCS1_MD5_SHA1, which is not registered.
+    pub fn iana_value(&self) -> Option<u16> {
+        let value = match self {
+            Self::RSA_PKCS1_MD5_SHA1 => return None,
+            Self::RSA_PKCS1_SHA1 => 0x0201,
+            Self::RSA_PKCS1_SHA224 => 0x0301,
+            Self::RSA_PKCS1_SHA256 => 0x0401,
+            Self::RSA_PKCS1_SHA384 => 0x0501,
+            Self::RSA_PKCS1_SHA512 => 0x0601,
+            Self::ECDSA_SHA1 => 0x0203,
+            Self::ECDSA_SHA224 => 0x0303,
+            Self::ECDSA_SHA256 | Self::ECDSA_SECP256R1_SHA256 => 0x0403,
+            Self::ECDSA_SHA384 | Self::ECDSA_SECP384R1_SHA384 => 0x0503,
+            Self::ECDSA_SHA512 | Self::ECDSA_SECP521R1_SHA512 => 0x0603,
+            Self::RSA_PSS_RSAE_SHA256 => 0x0804,
+            Self::RSA_PSS_RSAE_SHA384 => 0x0805,
+            Self::RSA_PSS_RSAE_SHA512 => 0x0806,
+            Self::RSA_PSS_PSS_SHA256 => 0x0809,
+            Self::RSA_PSS_PSS_SHA384 => 0x080A,
+            Self::RSA_PSS_PSS_SHA512 => 0x080B,
+            Self::MLDSA44 => 0x0904,
+            Self::MLDSA65 => 0x0905,
+            Self::MLDSA87 => 0x0906,
+        };
+        Some(value)
+    }
+}
+
+/// A TLS cipher suite, as named in the
+/// [IANA registry](https://www.iana.org/assignments/tls-parameters/tls-parameters.xhtml#tls-parameters-4).
+///
+/// Includes every cipher suite that s2n-tls supports. Any other value is
+/// reported as `Unknown`.
+#[non_exhaustive]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[allow(non_camel_case_types)]
+pub enum CipherSuite {
+    TLS_NULL_WITH_NULL_NULL,
+    TLS_RSA_WITH_RC4_128_MD5,
+    TLS_RSA_WITH_RC4_128_SHA,
+    TLS_RSA_WITH_3DES_EDE_CBC_SHA,
+    TLS_DHE_RSA_WITH_3DES_EDE_CBC_SHA,
+    TLS_RSA_WITH_AES_128_CBC_SHA,
+    TLS_DHE_RSA_WITH_AES_128_CBC_SHA,
+    TLS_RSA_WITH_AES_256_CBC_SHA,
+    TLS_DHE_RSA_WITH_AES_256_CBC_SHA,
+    TLS_RSA_WITH_AES_128_CBC_SHA256,
+    TLS_RSA_WITH_AES_256_CBC_SHA256,
+    TLS_DHE_RSA_WITH_AES_128_CBC_SHA256,
+    TLS_DHE_RSA_WITH_AES_256_CBC_SHA256,
+    TLS_RSA_WITH_AES_128_GCM_SHA256,
+    TLS_RSA_WITH_AES_256_GCM_SHA384,
+    TLS_DHE_RSA_WITH_AES_128_GCM_SHA256,
+    TLS_DHE_RSA_WITH_AES_256_GCM_SHA384,
+    TLS_AES_128_GCM_SHA256,
+    TLS_AES_256_GCM_SHA384,
+    TLS_CHACHA20_POLY1305_SHA256,
+    TLS_ECDHE_ECDSA_WITH_AES_128_CBC_SHA,
+    TLS_ECDHE_ECDSA_WITH_AES_256_CBC_SHA,
+    TLS_ECDHE_RSA_WITH_RC4_128_SHA,
+    TLS_ECDHE_RSA_WITH_3DES_EDE_CBC_SHA,
+    TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA,
+    TLS_ECDHE_RSA_WITH_AES_256_CBC_SHA,
+    TLS_ECDHE_ECDSA_WITH_AES_128_CBC_SHA256,
+    TLS_ECDHE_ECDSA_WITH_AES_256_CBC_SHA384,
+    TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA256,
+    TLS_ECDHE_RSA_WITH_AES_256_CBC_SHA384,
+    TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
+    TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384,
+    TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
+    TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384,
+    TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+    TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
+    TLS_DHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+    Unknown(u16),
+}
+
+impl CipherSuite {
+    /// The IANA value of the cipher suite.
+    pub fn iana_value(&self) -> u16 {
+        match self {
+            Self::TLS_NULL_WITH_NULL_NULL => 0x0000,
+            Self::TLS_RSA_WITH_RC4_128_MD5 => 0x0004,
+            Self::TLS_RSA_WITH_RC4_128_SHA => 0x0005,
+            Self::TLS_RSA_WITH_3DES_EDE_CBC_SHA => 0x000A,
+            Self::TLS_DHE_RSA_WITH_3DES_EDE_CBC_SHA => 0x0016,
+            Self::TLS_RSA_WITH_AES_128_CBC_SHA => 0x002F,
+            Self::TLS_DHE_RSA_WITH_AES_128_CBC_SHA => 0x0033,
+            Self::TLS_RSA_WITH_AES_256_CBC_SHA => 0x0035,
+            Self::TLS_DHE_RSA_WITH_AES_256_CBC_SHA => 0x0039,
+            Self::TLS_RSA_WITH_AES_128_CBC_SHA256 => 0x003C,
+            Self::TLS_RSA_WITH_AES_256_CBC_SHA256 => 0x003D,
+            Self::TLS_DHE_RSA_WITH_AES_128_CBC_SHA256 => 0x0067,
+            Self::TLS_DHE_RSA_WITH_AES_256_CBC_SHA256 => 0x006B,
+            Self::TLS_RSA_WITH_AES_128_GCM_SHA256 => 0x009C,
+            Self::TLS_RSA_WITH_AES_256_GCM_SHA384 => 0x009D,
+            Self::TLS_DHE_RSA_WITH_AES_128_GCM_SHA256 => 0x009E,
+            Self::TLS_DHE_RSA_WITH_AES_256_GCM_SHA384 => 0x009F,
+            Self::TLS_AES_128_GCM_SHA256 => 0x1301,
+            Self::TLS_AES_256_GCM_SHA384 => 0x1302,
+            Self::TLS_CHACHA20_POLY1305_SHA256 => 0x1303,
+            Self::TLS_ECDHE_ECDSA_WITH_AES_128_CBC_SHA => 0xC009,
+            Self::TLS_ECDHE_ECDSA_WITH_AES_256_CBC_SHA => 0xC00A,
+            Self::TLS_ECDHE_RSA_WITH_RC4_128_SHA => 0xC011,
+            Self::TLS_ECDHE_RSA_WITH_3DES_EDE_CBC_SHA => 0xC012,
+            Self::TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA => 0xC013,
+            Self::TLS_ECDHE_RSA_WITH_AES_256_CBC_SHA => 0xC014,
+            Self::TLS_ECDHE_ECDSA_WITH_AES_128_CBC_SHA256 => 0xC023,
+            Self::TLS_ECDHE_ECDSA_WITH_AES_256_CBC_SHA384 => 0xC024,
+            Self::TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA256 => 0xC027,
+            Self::TLS_ECDHE_RSA_WITH_AES_256_CBC_SHA384 => 0xC028,
+            Self::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256 => 0xC02B,
+            Self::TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384 => 0xC02C,
+            Self::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256 => 0xC02F,
+            Self::TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384 => 0xC030,
+            Self::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256 => 0xCCA8,
+            Self::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256 => 0xCCA9,
+            Self::TLS_DHE_RSA_WITH_CHACHA20_POLY1305_SHA256 => 0xCCAA,
+            Self::Unknown(value) => *value,
+        }
+    }
+
+    /// Whether the cipher suite can only be used with TLS1.3.
+    pub fn is_tls13(&self) -> bool {
+        matches!(
+            self,
+            Self::TLS_AES_128_GCM_SHA256
+                | Self::TLS_AES_256_GCM_SHA384
+                | Self::TLS_CHACHA20_POLY1305_SHA256
+        )
+    }
+}
+
+impl From<u16> for CipherSuite {
+    fn from(value: u16) -> Self {
+        match value {
+            0x0000 => Self::TLS_NULL_WITH_NULL_NULL,
+            0x0004 => Self::TLS_RSA_WITH_RC4_128_MD5,
+            0x0005 => Self::TLS_RSA_WITH_RC4_128_SHA,
+            0x000A => Self::TLS_RSA_WITH_3DES_EDE_CBC_SHA,
+            0x0016 => Self::TLS_DHE_RSA_WITH_3DES_EDE_CBC_SHA,
+            0x002F => Self::TLS_RSA_WITH_AES_128_CBC_SHA,
+            0x0033 => Self::TLS_DHE_RSA_WITH_AES_128_CBC_SHA,
+            0x0035 => Self::TLS_RSA_WITH_AES_256_CBC_SHA,
+            0x0039 => Self::TLS_DHE_RSA_WITH_AES_256_CBC_SHA,
+            0x003C => Self::TLS_RSA_WITH_AES_128_CBC_SHA256,
+            0x003D => Self::TLS_RSA_WITH_AES_256_CBC_SHA256,
+            0x0067 => Self::TLS_DHE_RSA_WITH_AES_128_CBC_SHA256,
+            0x006B => Self::TLS_DHE_RSA_WITH_AES_256_CBC_SHA256,
+            0x009C => Self::TLS_RSA_WITH_AES_128_GCM_SHA256,
+            0x009D => Self::TLS_RSA_WITH_AES_256_GCM_SHA384,
+            0x009E => Self::TLS_DHE_RSA_WITH_AES_128_GCM_SHA256,
+            0x009F => Self::TLS_DHE_RSA_WITH_AES_256_GCM_SHA384,
+            0x1301 => Self::TLS_AES_128_GCM_SHA256,
+            0x1302 => Self::TLS_AES_256_GCM_SHA384,
+            0x1303 => Self::TLS_CHACHA20_POLY1305_SHA256,
+            0xC009 => Self::TLS_ECDHE_ECDSA_WITH_AES_128_CBC_SHA,
+            0xC00A => Self::TLS_ECDHE_ECDSA_WITH_AES_256_CBC_SHA,
+            0xC011 => Self::TLS_ECDHE_RSA_WITH_RC4_128_SHA,
+            0xC012 => Self::TLS_ECDHE_RSA_WITH_3DES_EDE_CBC_SHA,
+            0xC013 => Self::TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA,
+            0xC014 => Self::TLS_ECDHE_RSA_WITH_AES_256_CBC_SHA,
+            0xC023 => Self::TLS_ECDHE_ECDSA_WITH_AES_128_CBC_SHA256,
+            0xC024 => Self::TLS_ECDHE_ECDSA_WITH_AES_256_CBC_SHA384,
+            0xC027 => Self::TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA256,
+            0xC028 => Self::TLS_ECDHE_RSA_WITH_AES_256_CBC_SHA384,
+            0xC02B => Self::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
+            0xC02C => Self::TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384,
+            0xC02F => Self::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
+            0xC030 => Self::TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384,
+            0xCCA8 => Self::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+            0xCCA9 => Self::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
+            0xCCAA => Self::TLS_DHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+            value => Self::Unknown(value),
+        }
+    }
+}
+
+impl From<CipherSuite> for u16 {
+    fn from(input: CipherSuite) -> Self {
+        input.iana_value()
+    }
+}
+
+/// A key exchange group, as named in the
+/// [IANA registry](https://www.iana.org/assignments/tls-parameters/tls-parameters.xhtml#tls-parameters-8).
+///
+/// Includes the elliptic curves and post-quantum groups that s2n-tls supports.
+/// The Kyber groups use the values from drafts that preceded ML-KEM, and
+/// will not be negotiated with most peers. Any other value is reported as `Unknown`.
+///
+/// s2n-tls only reports the name of the negotiated group, not its IANA value.
+/// A negotiated group that isn't listed here is reported as `Unknown(0)`,
+/// since 0 is not assigned to any group.
+#[non_exhaustive]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[allow(non_camel_case_types)]
+pub enum NamedGroup {
+    SECP256R1,
+    SECP384R1,
+    SECP521R1,
+    X25519,
+    MLKEM1024,
+    SECP256R1_MLKEM768,
+    X25519_MLKEM768,
+    SECP384R1_MLKEM1024,
+    X25519_KYBER512_R3,
+    SECP256R1_KYBER512_R3,
+    SECP384R1_KYBER768_R3,
+    SECP521R1_KYBER1024_R3,
+    X25519_KYBER768_DRAFT00,
+    SECP256R1_KYBER768_DRAFT00,
+    Unknown(u16),
+}
+
+impl NamedGroup {
+    /// Maps the names returned by [s2n_connection_get_key_exchange_group].
+    ///
+    /// Unrecognized names are mapped to `Unknown(0)`.
+    pub(crate) fn from_name(name: &str) -> Self {
+        match name {
+            "secp256r1" => Self::SECP256R1,
+            "secp384r1" => Self::SECP384R1,
+            "secp521r1" => Self::SECP521R1,
+            "x25519" => Self::X25519,
+            "MLKEM1024" => Self::MLKEM1024,
+            "SecP256r1MLKEM768" => Self::SECP256R1_MLKEM768,
+            "X25519MLKEM768" => Self::X25519_MLKEM768,
+            "SecP384r1MLKEM1024" => Self::SECP384R1_MLKEM1024,
+            "x25519_kyber-512-r3" => Self::X25519_KYBER512_R3,
+            "secp256r1_kyber-512-r3" => Self::SECP256R1_KYBER512_R3,
+            "secp384r1_kyber-768-r3" => Self::SECP384R1_KYBER768_R3,
+            "secp521r1_kyber-1024-r3" => Self::SECP521R1_KYBER1024_R3,
+            "X25519Kyber768Draft00" => Self::X25519_KYBER768_DRAFT00,
+            "SecP256r1Kyber768Draft00" => Self::SECP256R1_KYBER768_DRAFT00,
+            _ => Self::Unknown(0),
+        }
+    }
+
+    /// The IANA value of the group.
+    pub fn iana_value(&self) -> u16 {
+        match self {
+            Self::SECP256R1 => 0x0017,
+            Self::SECP384R1 => 0x0018,
+            Self::SECP521R1 => 0x0019,
+            Self::X25519 => 0x001D,
+            Self::MLKEM1024 => 0x0202,
+            Self::SECP256R1_MLKEM768 => 0x11EB,
+            Self::X25519_MLKEM768 => 0x11EC,
+            Self::SECP384R1_MLKEM1024 => 0x11ED,
+            Self::X25519_KYBER512_R3 => 0x2F39,
+            Self::SECP256R1_KYBER512_R3 => 0x2F3A,
+            Self::SECP384R1_KYBER768_R3 => 0x2F3C,
+            Self::SECP521R1_KYBER1024_R3 => 0x2F3D,
+            Self::X25519_KYBER768_DRAFT00 => 0x6399,
+            Self::SECP256R1_KYBER768_DRAFT00 => 0x639A,
+            Self::Unknown(value) => *value,
+        }
+    }
+
+    /// Whether the group uses a post-quantum KEM, either alone or as part of
+    /// a hybrid with an elliptic curve.
+    ///
+    /// Always false for `Unknown` groups.
+    pub fn is_post_quantum(&self) -> bool {
+        matches!(self, Self::MLKEM1024) || self.is_hybrid()
+    }
+
+    /// Whether the group combines an elliptic curve with a post-quantum KEM.
+    ///
+    /// Always false for `Unknown` groups.
+    pub fn is_hybrid(&self) -> bool {
+        matches!(
+            self,
+            Self::SECP256R1_MLKEM768
+                | Self::X25519_MLKEM768
+                | Self::SECP384R1_MLKEM1024
+                | Self::X25519_KYBER512_R3
+                | Self::SECP256R1_KYBER512_R3
+                | Self::SECP384R1_KYBER768_R3
+                | Self::SECP521R1_KYBER1024_R3
+                | Self::X25519_KYBER768_DRAFT00
+                | Self::SECP256R1_KYBER768_DRAFT00
+        )
+    }
+}
+
+impl From<u16> for NamedGroup {
+    fn from(value: u16) -> Self {
+        match value {
+            0x0017 => Self::SECP256R1,
+            0x0018 => Self::SECP384R1,
+            0x0019 => Self::SECP521R1,
+            0x001D => Self::X25519,
+            0x0202 => Self::MLKEM1024,
+            0x11EB => Self::SECP256R1_MLKEM768,
+            0x11EC => Self::X25519_MLKEM768,
+            0x11ED => Self::SECP384R1_MLKEM1024,
+            0x2F39 => Self::X25519_KYBER512_R3,
+            0x2F3A => Self::SECP256R1_KYBER512_R3,
+            0x2F3C => Self::SECP384R1_KYBER768_R3,
+            0x2F3D => Self::SECP521R1_KYBER1024_R3,
+            0x6399 => Self::X25519_KYBER768_DRAFT00,
+            0x639A => Self::SECP256R1_KYBER768_DRAFT00,
+            value => Self::Unknown(value),
+        }
+    }
+}
+
+impl From<NamedGroup> for u16 {
+    fn from(input: NamedGroup) -> Self {
+        input.iana_value()
+    }
+}
+
+/// The shape of a TLS handshake, as a set of flags.
+///
+/// Parsed from [s2n_connection_get_handshake_type_name]. Some flags only
+/// apply to a single protocol version: for example, OCSP_STATUS is only
+/// reported for TLS1.2, and HELLO_RETRY_REQUEST only for TLS1.3.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct HandshakeType(u16);
+
+impl HandshakeType {
+    /// The handshake has negotiated enough to choose which messages to send.
+    pub const NEGOTIATED: Self = Self(1 << 0);
+    /// A full handshake was performed, rather than resuming a previous session.
+    pub const FULL_HANDSHAKE: Self = Self(1 << 1);
+    /// The server requested a client certificate.
+    pub const CLIENT_AUTH: Self = Self(1 << 2);
+    /// The server requested a client certificate, but the client didn't send one.
+    pub const NO_CLIENT_CERT: Self = Self(1 << 3);
+    /// TLS1.2 only: the key exchange used an ephemeral key.
+    /// TLS1.3 key exchanges always do.
+    pub const TLS12_PERFECT_FORWARD_SECRECY: Self = Self(1 << 4);
+    /// TLS1.2 only: the server sent an OCSP response.
+    pub const OCSP_STATUS: Self = Self(1 << 5);
+    /// TLS1.2 only: the server sent a session ticket.
+    pub const WITH_SESSION_TICKET: Self = Self(1 << 6);
+    /// TLS1.2 only: the application protocol was negotiated with NPN.
+    pub const WITH_NPN: Self = Self(1 << 7);
+    /// TLS1.3 only: the server sent a HelloRetryRequest.
+    pub const HELLO_RETRY_REQUEST: Self = Self(1 << 8);
+    /// TLS1.3 only: ChangeCipherSpec messages were sent for middlebox compatibility.
+    pub const MIDDLEBOX_COMPAT: Self = Self(1 << 9);
+    /// TLS1.3 only: the server accepted early data.
+    pub const WITH_EARLY_DATA: Self = Self(1 << 10);
+    /// TLS1.3 only: the client sent a ChangeCipherSpec message before the server's response.
+    pub const EARLY_CLIENT_CCS: Self = Self(1 << 11);
+
+    const NAMES: [(Self, &'static str); 12] = [
+        (Self::NEGOTIATED, "NEGOTIATED"),
+        (Self::FULL_HANDSHAKE, "FULL_HANDSHAKE"),
+        (Self::CLIENT_AUTH, "CLIENT_AUTH"),
+        (Self::NO_CLIENT_CERT, "NO_CLIENT_CERT"),
+        (
+            Self::TLS12_PERFECT_FORWARD_SECRECY,
+            "TLS12_PERFECT_FORWARD_SECRECY",
+        ),
+        (Self::OCSP_STATUS, "OCSP_STATUS"),
+        (Self::WITH_SESSION_TICKET, "WITH_SESSION_TICKET"),
+        (Self::WITH_NPN, "WITH_NPN"),
+        (Self::HELLO_RETRY_REQUEST, "HELLO_RETRY_REQUEST"),
+        (Self::MIDDLEBOX_COMPAT, "MIDDLEBOX_COMPAT"),
+        (Self::WITH_EARLY_DATA, "WITH_EARLY_DATA"),
+        (Self::EARLY_CLIENT_CCS, "EARLY_CLIENT_CCS"),
+    ];
+
+    /// No flags, which s2n-tls calls "INITIAL".
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether all the flags in `other` are set.
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Parses the names returned by [s2n_connection_get_handshake_type_name],
+    /// like "NEGOTIATED|FULL_HANDSHAKE|MIDDLEBOX_COMPAT".
+    pub(crate) fn from_name(name: &str) -> Result<Self, Error> {
+        if name == "INITIAL" {
+            return Ok(Self::empty());
+        }
+        name.split('|')
+            .filter(|flag| !flag.is_empty())
+            .try_fold(Self::empty(), |flags, flag| {
+                let (value, _) = Self::NAMES
+                    .iter()
+                    .find(|(_, name)| *name == flag)
+                    .ok_or(Error::INVALID_INPUT)?;
+                Ok(flags | *value)
+            })
+    }
+
+    /// Reinterprets the version-specific flags for the negotiated protocol version.
+    ///
+    /// s2n-tls uses the same values for the TLS1.2 and TLS1.3 specific flags,
+    /// and caches handshake type names by value without the protocol version.
+    /// So a TLS1.3 handshake may be named with the TLS1.2 flags, or vice versa.
+    pub(crate) fn for_version(self, version: Version) -> Self {
+        // Common flags use bits 0-3. The TLS1.2 flags use bits 4-7, like
+        // the s2n-tls values, while the TLS1.3 flags are shifted up to bits 8-11.
+        let common = self.0 & 0x0F;
+        let specific = (self.0 & 0xF0) | ((self.0 >> 4) & 0xF0);
+        match version {
+            Version::TLS13 => Self(common | specific << 4),
+            _ => Self(common | specific),
+        }
+    }
+
+    /// Whether a full handshake was performed.
+    pub fn is_full_handshake(&self) -> bool {
+        self.contains(Self::NEGOTIATED | Self::FULL_HANDSHAKE)
+    }
+
+    /// Whether the handshake was abbreviated by using a PSK instead of
+    /// performing a full handshake.
+    ///
+    /// The PSK may come from a resumed session or, in TLS1.3, be an external PSK.
+    /// Use [Connection::resumed](`crate::connection::Connection::resumed()`)
+    /// to check whether a previous session was resumed.
+    pub fn is_abbreviated(&self) -> bool {
+        self.contains(Self::NEGOTIATED) && !self.contains(Self::FULL_HANDSHAKE)
+    }
+
+    /// Whether the client sent a certificate for client authentication.
+    pub fn is_client_authenticated(&self) -> bool {
+        self.contains(Self::CLIENT_AUTH) && !self.contains(Self::NO_CLIENT_CERT)
+    }
+
+    /// Whether the server had to request a different key share.
+    pub fn is_hello_retry(&self) -> bool {
+        self.contains(Self::HELLO_RETRY_REQUEST)
+    }
+
+    /// Whether early data was accepted.
+    pub fn is_early_data(&self) -> bool {
+        self.contains(Self::WITH_EARLY_DATA)
+    }
+}
+
+impl ops::BitOr for HandshakeType {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl ops::BitOrAssign for HandshakeType {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl ops::BitAnd for HandshakeType {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl fmt::Debug for HandshakeType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "HandshakeType(INITIAL)");
+        }
+        let mut names = Self::NAMES
+            .iter()
+            .filter(|(value, _)| self.contains(*value))
+            .map(|(_, name)| *name);
+        write!(f, "HandshakeType(")?;
+        if let Some(first) = names.next() {
+            write!(f, "{first}")?;
+        }
+        for name in names {
+            write!(f, " | {name}")?;
+        }
+        write!(f, ")")
+    }
+}
+
 /// Corresponds to [s2n_peer_key_update].
 #[non_exhaustive]
 #[derive(Debug, PartialEq, Copy, Clone)]
@@ -256,7 +914,7 @@ impl From<PeerKeyUpdate> for s2n_peer_key_update::Type {
 }
 
 #[non_exhaustive]
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Copy, Clone)]
 pub enum PskMode {
     Resumption,
     External,
@@ -303,3 +961,32 @@ impl From<SerializationVersion> for s2n_serialization_version::Type {
         }
     }
 }
+
+/// Corresponds to [s2n_early_data_status_t].
+#[non_exhaustive]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum EarlyDataStatus {
+    /// Early data is in progress.
+    Ok,
+    /// The client did not request early data, so none was sent or received.
+    NotRequested,
+    /// The client requested early data, but the server rejected the request.
+    Rejected,
+    /// All early data was successfully sent and received.
+    End,
+}
+
+impl TryFrom<s2n_early_data_status_t::Type> for EarlyDataStatus {
+    type Error = Error;
+
+    fn try_from(input: s2n_early_data_status_t::Type) -> Result<Self, Self::Error> {
+        let status = match input {
+            s2n_early_data_status_t::OK => Self::Ok,
+            s2n_early_data_status_t::NOT_REQUESTED => Self::NotRequested,
+            s2n_early_data_status_t::REJECTED => Self::Rejected,
+            s2n_early_data_status_t::END => Self::End,
+            _ => return Err(Error::INVALID_INPUT),
+        };
+        Ok(status)
+    }
+}
@@ -49,7 +49,11 @@ impl From<libc::c_int> for ErrorType {
 
 enum Context {
     Bindings(ErrorType, &'static str, &'static str),
-    Code(s2n_status_code::Type, Errno),
+    Code(
+        s2n_status_code::Type,
+        Errno,
+        #[cfg(feature = "stacktrace")] Option<Stacktrace>,
+    ),
     Application(Box<dyn std::error::Error + Send + Sync + 'static>),
 }
 
@@ -195,7 +199,12 @@ impl Error {
             //# an error: s2n_errno = S2N_ERR_T_OK
             *s2n_errno = s2n_error_type::OK as _;
 
-            Self(Context::Code(code, errno()))
+            let errno = errno();
+            #[cfg(feature = "stacktrace")]
+            let context = Context::Code(code, errno, Stacktrace::capture(code));
+            #[cfg(not(feature = "stacktrace"))]
+            let context = Context::Code(code, errno);
+            Self(context)
         }
     }
 
@@ -204,7 +213,7 @@ impl Error {
         match self.0 {
             Context::Bindings(_, name, _) => name,
             Context::Application(_) => "ApplicationError",
-            Context::Code(code, _) => unsafe {
+            Context::Code(code, ..) => unsafe {
                 // Safety: we assume the string has a valid encoding coming from s2n
                 cstr_to_str(s2n_strerror_name(code))
             },
@@ -216,7 +225,7 @@ impl Error {
         match self.0 {
             Context::Bindings(_, _, msg) => msg,
             Context::Application(_) => "An error occurred while executing application code",
-            Context::Code(code, _) => unsafe {
+            Context::Code(code, ..) => unsafe {
                 // Safety: we assume the string has a valid encoding coming from s2n
                 cstr_to_str(s2n_strerror(code, core::ptr::null()))
             },
@@ -227,7 +236,7 @@ impl Error {
     pub fn debug(&self) -> Option<&'static str> {
         match self.0 {
             Context::Bindings(_, _, _) | Context::Application(_) => None,
-            Context::Code(code, _) => unsafe {
+            Context::Code(code, ..) => unsafe {
                 let debug_info = s2n_strerror_debug(code, core::ptr::null());
 
                 // The debug string should be set to a constant static string
@@ -249,7 +258,7 @@ impl Error {
         match self.0 {
             Context::Bindings(error_type, _, _) => error_type,
             Context::Application(_) => ErrorType::Application,
-            Context::Code(code, _) => unsafe { ErrorType::from(s2n_error_get_type(code)) },
+            Context::Code(code, ..) => unsafe { ErrorType::from(s2n_error_get_type(code)) },
         }
     }
 
@@ -257,7 +266,7 @@ impl Error {
         match self.0 {
             Context::Bindings(_, _, _) => ErrorSource::Bindings,
             Context::Application(_) => ErrorSource::Application,
-            Context::Code(_, _) => ErrorSource::Library,
+            Context::Code(..) => ErrorSource::Library,
         }
     }
 
@@ -275,6 +284,117 @@ impl Error {
     pub fn is_retryable(&self) -> bool {
         matches!(self.kind(), ErrorType::Blocked)
     }
+
+    /// The C stack trace captured when the error occurred.
+    ///
+    /// Only available for ErrorSource::Library errors, and only if stack traces
+    /// were enabled with [`set_stacktraces_enabled()`] when the error occurred.
+    #[cfg(feature = "stacktrace")]
+    pub fn stacktrace(&self) -> Option<&[String]> {
+        match &self.0 {
+            Context::Code(_, _, Some(stacktrace)) => Some(&stacktrace.0),
+            _ => None,
+        }
+    }
+}
+
+/// The frames of a C stack trace, most recent first.
+#[cfg(feature = "stacktrace")]
+struct Stacktrace(Vec<String>);
+
+#[cfg(feature = "stacktrace")]
+impl Stacktrace {
+    /// Copies the stack trace that s2n-tls recorded for the last error
+    /// on this thread.
+    ///
+    /// Blocked errors are expected during normal operation,
+    /// so their stack traces are not worth the cost of capturing.
+    ///
+    /// # Safety
+    ///
+    /// Must be called immediately after an s2n-tls error, before any other
+    /// s2n-tls call on this thread overwrites the stack trace.
+    unsafe fn capture(code: s2n_status_code::Type) -> Option<Self> {
+        if !s2n_stack_traces_enabled() {
+            return None;
+        }
+        if ErrorType::from(s2n_error_get_type(code)) == ErrorType::Blocked {
+            return None;
+        }
+        let output = print_stacktrace()?;
+        let (_, frames) = output.split_once("Stacktrace is:\n")?;
+        let frames = frames
+            .lines()
+            .filter(|frame| !frame.is_empty())
+            .map(String::from)
+            .collect();
+        Some(Self(frames))
+    }
+}
+
+/// Corresponds to [s2n_print_stacktrace].
+///
+/// s2n_stacktrace is opaque in the public API, so the stack trace
+/// is printed to an in-memory stream and read back.
+///
+/// Failures are not converted to an [`Error`], which would
+/// itself try to capture a stack trace.
+#[cfg(feature = "stacktrace")]
+unsafe fn print_stacktrace() -> Option<String> {
+    let mut buffer: *mut c_char = core::ptr::null_mut();
+    let mut len: libc::size_t = 0;
+    let stream = libc::open_memstream(&mut buffer, &mut len);
+    if stream.is_null() {
+        return None;
+    }
+
+    let printed = s2n_print_stacktrace(stream) == s2n_status_code::SUCCESS;
+    if !printed {
+        *s2n_errno_location() = s2n_error_type::OK as _;
+    }
+    // Closing the stream sets the final buffer and length
+    let closed = libc::fclose(stream) == 0;
+
+    let output = if printed && closed && !buffer.is_null() {
+        let bytes = core::slice::from_raw_parts(buffer as *const u8, len);
+        Some(String::from_utf8_lossy(bytes).into_owned())
+    } else {
+        None
+    };
+    libc::free(buffer as *mut libc::c_void);
+    output
+}
+
+#[cfg(feature = "stacktrace")]
+const STACKTRACES_UNSUPPORTED: Error = Error::bindings(
+    ErrorType::UsageError,
+    "StacktraceUnsupported",
+    "s2n-tls was built without stack trace support",
+);
+
+/// Enables capturing the C stack trace for each error reported by s2n-tls.
+///
+/// Captured stack traces are returned by [`Error::stacktrace()`] and included
+/// in the `Debug` output of errors. Capturing stack traces is expensive, so
+/// they are disabled by default, and never captured for [`ErrorType::Blocked`]
+/// errors. This setting applies to all threads.
+///
+/// Stack traces require the `stacktrace` feature and a platform
+/// that provides execinfo.h, like Linux.
+///
+/// Corresponds to [s2n_stack_traces_enabled_set].
+#[cfg(feature = "stacktrace")]
+pub fn set_stacktraces_enabled(enabled: bool) -> Result<(), Error> {
+    if enabled {
+        crate::init::init();
+        // Without execinfo, s2n-tls fails every request for a stack trace.
+        // Check before enabling them, while errors still don't request one.
+        if unsafe { print_stacktrace() }.is_none() {
+            return Err(STACKTRACES_UNSUPPORTED);
+        }
+    }
+    unsafe { s2n_stack_traces_enabled_set(enabled).into_result() }?;
+    Ok(())
 }
 
 #[cfg(feature = "quic")]
@@ -290,7 +410,7 @@ impl Error {
     pub fn alert(&self) -> Option<u8> {
         match self.0 {
             Context::Bindings(_, _, _) | Context::Application(_) => None,
-            Context::Code(code, _) => {
+            Context::Code(code, ..) => {
                 let mut alert = 0;
                 let r = unsafe { s2n_error_get_alert(code, &mut alert) };
                 match r.into_result() {
@@ -327,7 +447,7 @@ impl From<Error> for std::io::Error {
     fn from(input: Error) -> Self {
         let kind = match input.kind() {
             ErrorType::IOError => {
-                if let Context::Code(_, errno) = input.0 {
+                if let Context::Code(_, errno, ..) = input.0 {
                     let bare = std::io::Error::from_raw_os_error(errno.0);
                     bare.kind()
                 } else {
@@ -350,7 +470,7 @@ impl fmt::Debug for Error {
         }
 
         let mut s = f.debug_struct("Error");
-        if let Context::Code(code, _) = self.0 {
+        if let Context::Code(code, ..) = self.0 {
             s.field("code", &code);
         }
 
@@ -366,10 +486,15 @@ impl fmt::Debug for Error {
         // "errno" is only known to be meaningful for IOErrors.
         // However, it has occasionally proved useful for debugging
         // other errors, so include it for all errors.
-        if let Context::Code(_, errno) = self.0 {
+        if let Context::Code(_, errno, ..) = self.0 {
             s.field("errno", &errno.to_string());
         }
 
+        #[cfg(feature = "stacktrace")]
+        if let Context::Code(_, _, Some(stacktrace)) = &self.0 {
+            s.field("stacktrace", &stacktrace.0);
+        }
+
         s.finish()
     }
 }
@@ -508,4 +633,42 @@ mod tests {
         assert_eq!(error.debug(), None);
         assert_eq!(error.source(), ErrorSource::Bindings);
     }
+
+    #[cfg(feature = "stacktrace")]
+    #[test]
+    fn stacktrace() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::config;
+
+        // Produce a real library error, so that s2n-tls records a stack trace
+        let library_error = || {
+            let mut config = config::Builder::new();
+            let err = config.load_pem(b"not a cert", b"not a key").unwrap_err();
+            assert_eq!(err.source(), ErrorSource::Library);
+            err
+        };
+
+        set_stacktraces_enabled(true)?;
+        let error = library_error();
+        // Blocked errors are not captured, even while stack traces are enabled.
+        // S2N_ERR_IO_BLOCKED is the first error of the blocked type.
+        let blocked = unsafe {
+            *s2n_errno_location() = (s2n_error_type::BLOCKED << 26) as _;
+            Error::capture()
+        };
+        set_stacktraces_enabled(false)?;
+        assert_eq!(blocked.kind(), ErrorType::Blocked);
+        assert!(blocked.stacktrace().is_none());
+
+        let stacktrace = error.stacktrace().unwrap();
+        assert!(!stacktrace.is_empty());
+        assert!(format!("{error:?}").contains("stacktrace"));
+
+        let error = library_error();
+        assert!(error.stacktrace().is_none());
+        assert!(!format!("{error:?}").contains("stacktrace"));
+
+        let bindings_error = Version::try_from(0).unwrap_err();
+        assert!(bindings_error.stacktrace().is_none());
+        Ok(())
+    }
 }
@@ -3,18 +3,44 @@
 
 use crate::{
     enums::FipsMode,
-    error::{Error, Fallible},
+    error::{Error, ErrorType, Fallible},
 };
 use s2n_tls_sys::*;
-use std::sync::Once;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Once,
+};
 
 static S2N_INIT: Once = Once::new();
+/// Set if initialization failed, after which s2n-tls can't be used.
+static S2N_INIT_FAILED: AtomicBool = AtomicBool::new(false);
+
+const ALREADY_INITIALIZED: Error = Error::bindings(
+    ErrorType::UsageError,
+    "InitError",
+    "s2n-tls has already been initialized",
+);
+
+const INIT_FAILED: Error = Error::bindings(
+    ErrorType::UsageError,
+    "InitError",
+    "s2n-tls failed to initialize",
+);
 
 /// # Safety
 ///
 /// This function should only be called once
-unsafe fn global_init() -> Result<(), Error> {
+unsafe fn global_init(builder: &Builder) -> Result<(), Error> {
     mem::init()?;
+    if !builder.atexit {
+        s2n_disable_atexit().into_result()?;
+    }
+    if !builder.crypto_init {
+        s2n_crypto_disable_init().into_result()?;
+    }
+    if let Some(source) = &builder.random_source {
+        rand::init(source.clone())?;
+    }
     s2n_init().into_result()?;
     Ok(())
 }
@@ -23,8 +49,12 @@ thread_local! {
     static S2N_THREAD: Thread = {
         S2N_INIT.call_once(|| unsafe {
             // Safety: by using `Once` we can ensure the library is initialized once
-            global_init().expect("could not initialize s2n-tls");
+            global_init(&Builder::default()).expect("could not initialize s2n-tls");
         });
+        assert!(
+            !S2N_INIT_FAILED.load(Ordering::Acquire),
+            "could not initialize s2n-tls"
+        );
         Thread
     };
 }
@@ -45,6 +75,159 @@ pub fn init() {
     S2N_THREAD.with(|_| ());
 }
 
+/// Controls how the s2n-tls library is initialized.
+///
+/// The library is initialized with the default options the first time it is
+/// used, so a Builder must be used before any other s2n-tls API is called.
+#[derive(Clone)]
+pub struct Builder {
+    atexit: bool,
+    crypto_init: bool,
+    random_source: Option<Arc<dyn RandomSource>>,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            atexit: true,
+            crypto_init: true,
+            random_source: None,
+        }
+    }
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prevents s2n-tls from installing an atexit handler to clean up the library.
+    ///
+    /// Use this when s2n-tls is loaded by a shared library that may be unloaded
+    /// before the process exits, and clean up with a [`CleanupGuard`] instead.
+    ///
+    /// Corresponds to [s2n_disable_atexit].
+    pub fn disable_atexit(&mut self) -> &mut Self {
+        self.atexit = false;
+        self
+    }
+
+    /// Prevents s2n-tls from initializing and cleaning up libcrypto.
+    ///
+    /// Use this when the application manages libcrypto itself.
+    ///
+    /// Corresponds to [s2n_crypto_disable_init].
+    pub fn disable_crypto_init(&mut self) -> &mut Self {
+        self.crypto_init = false;
+        self
+    }
+
+    /// Sets the source of the entropy used to seed the s2n-tls random number generators.
+    ///
+    /// The source is not used when s2n-tls is operating in FIPS mode.
+    /// See [`RandomSource`] for what random data the source affects.
+    ///
+    /// Corresponds to [s2n_rand_set_callbacks].
+    pub fn set_random_source<T: RandomSource>(&mut self, source: T) -> &mut Self {
+        self.random_source = Some(Arc::new(source));
+        self
+    }
+
+    /// Initializes the library.
+    ///
+    /// Fails if the library was already initialized, either by a previous
+    /// call or implicitly by another s2n-tls API.
+    ///
+    /// Initialization can only be attempted once. If it fails, the library
+    /// is left unusable: later calls fail, and other s2n-tls APIs panic.
+    ///
+    /// Corresponds to [s2n_init].
+    pub fn init(&self) -> Result<(), Error> {
+        if S2N_INIT_FAILED.load(Ordering::Acquire) {
+            return Err(INIT_FAILED);
+        }
+        let mut result = Err(ALREADY_INITIALIZED);
+        S2N_INIT.call_once(|| unsafe {
+            // Safety: by using `Once` we can ensure the library is initialized once
+            result = global_init(self);
+            if result.is_err() {
+                S2N_INIT_FAILED.store(true, Ordering::Release);
+            }
+        });
+        result?;
+        init();
+        Ok(())
+    }
+}
+
+/// A source of entropy for the s2n-tls random number generators.
+///
+/// s2n-tls generates random data like the ClientHello and ServerHello random
+/// values with its own per-thread generators, which are seeded and refreshed
+/// from the RandomSource. Other random data, like ephemeral key shares and
+/// signatures, is generated by libcrypto. libcrypto only draws from the s2n-tls
+/// generators when s2n-tls can override its random number generator, which is
+/// not supported by AWS-LC, OpenSSL 3, or in FIPS mode.
+///
+/// So a source that always returns the same bytes only makes handshakes
+/// reproducible if s2n-tls overrides the libcrypto generator, and if each
+/// thread's generators are used in the same order. With the `unstable-testing`
+/// feature, `testing::DeterministicRandom` provides such a source for tests.
+///
+/// Use in conjunction with [`Builder::set_random_source()`].
+pub trait RandomSource: 'static + Send + Sync {
+    /// Fills `dest` with the entropy used to seed a random number generator.
+    ///
+    /// Corresponds to [s2n_rand_seed_callback].
+    fn seed(&self, dest: &mut [u8]) -> Result<(), Error>;
+
+    /// Fills `dest` with the entropy mixed in every time random data is generated.
+    ///
+    /// Corresponds to [s2n_rand_mix_callback].
+    fn mix(&self, dest: &mut [u8]) -> Result<(), Error> {
+        self.seed(dest)
+    }
+}
+
+/// Cleans up the thread-local resources used by s2n-tls on the current thread.
+///
+/// s2n-tls already does this when the thread exits, but not all platforms
+/// guarantee that thread-local destructors are run. Worker threads can call
+/// this directly before exiting instead.
+///
+/// Corresponds to [s2n_cleanup_thread].
+#[cfg(feature = "unstable-cleanup")]
+pub fn cleanup_thread() -> Result<(), Error> {
+    unsafe { s2n_cleanup_thread().into_result() }?;
+    Ok(())
+}
+
+/// Fully deinitializes the s2n-tls library when dropped.
+///
+/// Usually s2n-tls is cleaned up by an atexit handler. Applications that
+/// disable the handler with [`Builder::disable_atexit()`], for example because
+/// s2n-tls may be unloaded before the process exits, can use this guard instead.
+///
+/// The library cannot be initialized again after it is cleaned up.
+pub struct CleanupGuard(());
+
+impl CleanupGuard {
+    /// # Safety
+    ///
+    /// No s2n-tls API may be used after the guard is dropped. All connections,
+    /// configs, and other s2n-tls types must be dropped before the guard.
+    pub unsafe fn new() -> Self {
+        Self(())
+    }
+}
+
+impl Drop for CleanupGuard {
+    /// Corresponds to [s2n_cleanup_final].
+    fn drop(&mut self) {
+        let _ = unsafe { s2n_cleanup_final().into_result() };
+    }
+}
+
 /// Determines whether s2n-tls is operating in FIPS mode.
 ///
 /// It is possible to enable FIPS mode by enabling the `fips` feature flag.
@@ -62,6 +245,76 @@ pub fn fips_mode() -> Result<FipsMode, Error> {
     fips_mode.try_into()
 }
 
+mod rand {
+    use super::*;
+    use core::ffi::c_void;
+    use std::sync::OnceLock;
+
+    /// s2n_rand_set_callbacks has no context pointer, but can only be set once
+    /// because it must be called before s2n_init.
+    pub(super) static SOURCE: OnceLock<Arc<dyn RandomSource>> = OnceLock::new();
+
+    /// Corresponds to [s2n_rand_set_callbacks].
+    ///
+    /// # Safety
+    ///
+    /// Must be called before s2n_init.
+    pub unsafe fn init(source: Arc<dyn RandomSource>) -> Result<(), Error> {
+        SOURCE.set(source).map_err(|_| ALREADY_INITIALIZED)?;
+        s2n_rand_set_callbacks(
+            Some(rand_init_callback),
+            Some(rand_cleanup_callback),
+            Some(rand_seed_callback),
+            Some(rand_mix_callback),
+        )
+        .into_result()?;
+        Ok(())
+    }
+
+    unsafe extern "C" fn rand_init_callback() -> s2n_status_code::Type {
+        // no-op: the source is ready to use once set
+        s2n_status_code::SUCCESS
+    }
+
+    unsafe extern "C" fn rand_cleanup_callback() -> s2n_status_code::Type {
+        // no-op: the source is owned by the static
+        s2n_status_code::SUCCESS
+    }
+
+    pub(super) unsafe extern "C" fn rand_seed_callback(
+        data: *mut c_void,
+        size: u32,
+    ) -> s2n_status_code::Type {
+        fill(data, size, |source, dest| source.seed(dest))
+    }
+
+    pub(super) unsafe extern "C" fn rand_mix_callback(
+        data: *mut c_void,
+        size: u32,
+    ) -> s2n_status_code::Type {
+        fill(data, size, |source, dest| source.mix(dest))
+    }
+
+    unsafe fn fill(
+        data: *mut c_void,
+        size: u32,
+        f: impl FnOnce(&dyn RandomSource, &mut [u8]) -> Result<(), Error>,
+    ) -> s2n_status_code::Type {
+        let source = match SOURCE.get() {
+            Some(source) => source,
+            None => return s2n_status_code::FAILURE,
+        };
+        if data.is_null() {
+            return s2n_status_code::FAILURE;
+        }
+        let dest = core::slice::from_raw_parts_mut(data as *mut u8, size as usize);
+        match f(source.as_ref(), dest) {
+            Ok(()) => s2n_status_code::SUCCESS,
+            Err(_) => s2n_status_code::FAILURE,
+        }
+    }
+}
+
 mod mem {
     use super::*;
     use alloc::alloc::{alloc, dealloc, Layout};
@@ -139,3 +392,69 @@ mod mem {
         Layout::from_size_align(len as usize, ALIGNMENT).ok()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn already_initialized() {
+        init();
+        let err = Builder::new().disable_atexit().init().unwrap_err();
+        assert_eq!(err.name(), "InitError");
+        assert_eq!(err.kind(), ErrorType::UsageError);
+    }
+
+    /// Fills every buffer with a constant, and counts the calls.
+    struct TestRandom(AtomicUsize);
+
+    impl RandomSource for TestRandom {
+        fn seed(&self, dest: &mut [u8]) -> Result<(), Error> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            dest.fill(0xaa);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn random_source_after_init() {
+        init();
+        let mut builder = Builder::new();
+        builder.set_random_source(TestRandom(AtomicUsize::new(0)));
+        assert_eq!(builder.init().unwrap_err().name(), "InitError");
+    }
+
+    #[test]
+    fn random_source_callbacks() {
+        use core::ffi::c_void;
+
+        // The library is initialized by whichever test runs first, so
+        // invoke the registered callbacks directly.
+        let source = Arc::new(TestRandom(AtomicUsize::new(0)));
+        assert!(rand::SOURCE.set(source.clone()).is_ok());
+
+        let mut dest = [0u8; 45];
+        for callback in [rand::rand_seed_callback, rand::rand_mix_callback] {
+            dest.fill(0);
+            let result = unsafe { callback(dest.as_mut_ptr() as *mut c_void, dest.len() as u32) };
+            assert_eq!(result, s2n_status_code::SUCCESS);
+            assert!(dest.iter().all(|b| *b == 0xaa));
+        }
+        assert_eq!(source.0.load(Ordering::Relaxed), 2);
+
+        let result = unsafe { rand::rand_seed_callback(core::ptr::null_mut(), 1) };
+        assert_eq!(result, s2n_status_code::FAILURE);
+    }
+
+    #[cfg(feature = "unstable-cleanup")]
+    #[test]
+    fn cleanup_thread() {
+        std::thread::spawn(|| {
+            init();
+            super::cleanup_thread().unwrap();
+        })
+        .join()
+        .unwrap();
+    }
+}
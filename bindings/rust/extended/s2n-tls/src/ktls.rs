@@ -0,0 +1,225 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Methods to offload TLS record encryption and decryption to the kernel (kTLS).
+//!
+//! See [the C API documentation](https://github.com/aws/s2n-tls/blob/main/api/unstable/ktls.h)
+//! for the primary documentation of the feature, including the current limitations.
+//!
+//! kTLS can only be enabled on a connection that reads and writes a socket
+//! through s2n-tls, so [`Connection::set_fd()`] must be called before
+//! enabling kTLS. Custom IO callbacks are not supported.
+//!
+//! This is an unstable s2n API.
+
+use s2n_tls_sys::*;
+use std::task::Poll;
+
+use crate::{
+    config,
+    connection::Connection,
+    error::{Error, Fallible, Pollable},
+};
+
+/// The reason that kTLS could not be enabled on a connection.
+///
+/// Use [`Error::ktls_error()`] to retrieve the reason from an error returned by
+/// [`Connection::enable_ktls_send()`] or [`Connection::enable_ktls_recv()`].
+///
+/// If kTLS could not be enabled, the connection can still be used without kTLS.
+#[non_exhaustive]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum KtlsError {
+    /// s2n-tls does not support kTLS on this platform.
+    UnsupportedPlatform,
+    /// The kernel rejected the request to enable kTLS on the socket.
+    /// The TLS kernel module may need to be loaded with `modprobe tls`.
+    KernelRejected,
+    /// The connection negotiated parameters, like the cipher suite or protocol
+    /// version, that s2n-tls does not support with kTLS.
+    UnsupportedConnection,
+    /// The connection may renegotiate, which kTLS does not support.
+    Renegotiation,
+    /// The connection is configured with custom IO rather than a file descriptor.
+    ManagedIoRequired,
+    /// The handshake has not completed yet.
+    HandshakeNotComplete,
+    /// The connection has buffered data which must be sent or received first.
+    PendingData,
+}
+
+impl Error {
+    /// If this error was caused by an attempt to enable kTLS, returns the reason.
+    pub fn ktls_error(&self) -> Option<KtlsError> {
+        let reason = match self.name() {
+            "S2N_ERR_KTLS_UNSUPPORTED_PLATFORM" => KtlsError::UnsupportedPlatform,
+            "S2N_ERR_KTLS_ENABLE" => KtlsError::KernelRejected,
+            "S2N_ERR_KTLS_UNSUPPORTED_CONN" => KtlsError::UnsupportedConnection,
+            "S2N_ERR_KTLS_RENEG" => KtlsError::Renegotiation,
+            "S2N_ERR_KTLS_MANAGED_IO" => KtlsError::ManagedIoRequired,
+            "S2N_ERR_HANDSHAKE_NOT_COMPLETE" => KtlsError::HandshakeNotComplete,
+            "S2N_ERR_RECORD_STUFFER_NEEDS_DRAINING" => KtlsError::PendingData,
+            _ => return None,
+        };
+        Some(reason)
+    }
+}
+
+impl config::Builder {
+    /// Allows kTLS to be enabled if a connection negotiates TLS1.3.
+    ///
+    /// This is considered "unsafe" because only linux kernel versions >= 6.14
+    /// support TLS1.3 key updates, and s2n-tls cannot detect whether the kernel
+    /// supports them. Sending or receiving a key update without kernel support
+    /// will cause the connection to fail.
+    ///
+    /// Corresponds to [s2n_config_ktls_enable_unsafe_tls13].
+    pub fn enable_ktls_unsafe_tls13(&mut self) -> Result<&mut Self, Error> {
+        unsafe { s2n_config_ktls_enable_unsafe_tls13(self.as_mut_ptr()).into_result() }?;
+        Ok(self)
+    }
+}
+
+impl Connection {
+    /// Sets the file descriptor that s2n-tls reads from and writes to.
+    ///
+    /// This replaces any IO callbacks set with [`Connection::set_receive_callback()`]
+    /// or [`Connection::set_send_callback()`]. The file descriptor is not owned
+    /// by the connection, and must remain open while the connection is in use.
+    ///
+    /// Corresponds to [s2n_connection_set_fd].
+    pub fn set_fd(&mut self, fd: libc::c_int) -> Result<&mut Self, Error> {
+        unsafe { s2n_connection_set_fd(self.as_ptr(), fd).into_result() }?;
+        Ok(self)
+    }
+
+    /// Enables sending using kTLS.
+    ///
+    /// Must be called after the handshake completes, and when there is no
+    /// pending application data that requires flushing.
+    ///
+    /// Corresponds to [s2n_connection_ktls_enable_send].
+    pub fn enable_ktls_send(&mut self) -> Result<&mut Self, Error> {
+        unsafe { s2n_connection_ktls_enable_send(self.as_ptr()).into_result() }?;
+        Ok(self)
+    }
+
+    /// Enables receiving using kTLS.
+    ///
+    /// Must be called after the handshake completes, and when there is no
+    /// buffered application data that requires reading.
+    ///
+    /// Corresponds to [s2n_connection_ktls_enable_recv].
+    pub fn enable_ktls_recv(&mut self) -> Result<&mut Self, Error> {
+        unsafe { s2n_connection_ktls_enable_recv(self.as_ptr()).into_result() }?;
+        Ok(self)
+    }
+
+    /// Sends up to `count` bytes of a file as application data, starting at `offset`.
+    ///
+    /// The copy from the file to the socket happens inside the kernel, so
+    /// this is only supported if kTLS is enabled for sending.
+    /// `fd` must be opened for reading and cannot be a socket.
+    ///
+    /// Returns the number of bytes sent, and may indicate a partial send.
+    ///
+    /// Corresponds to [s2n_sendfile].
+    pub fn poll_sendfile(
+        &mut self,
+        fd: libc::c_int,
+        offset: u64,
+        count: usize,
+    ) -> Poll<Result<usize, Error>> {
+        let offset: libc::off_t = offset.try_into().map_err(|_| Error::INVALID_INPUT)?;
+        let mut blocked = s2n_blocked_status::NOT_BLOCKED;
+        let mut bytes_written = 0;
+        let result = unsafe {
+            s2n_sendfile(
+                self.as_ptr(),
+                fd,
+                offset,
+                count,
+                &mut bytes_written,
+                &mut blocked,
+            )
+            .into_poll()
+        };
+        result.map_ok(|_| bytes_written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::ErrorType,
+        security,
+        testing::{build_config, TestPair},
+    };
+    use std::os::unix::io::AsRawFd;
+
+    // kTLS support depends on the platform, so either error is acceptable.
+    fn assert_ktls_error(error: Error, expected: KtlsError) {
+        let reason = error.ktls_error().expect("expected a kTLS error");
+        assert!(
+            reason == expected || reason == KtlsError::UnsupportedPlatform,
+            "unexpected kTLS error: {reason:?}"
+        );
+    }
+
+    #[test]
+    fn handshake_not_complete() -> Result<(), Error> {
+        let config = build_config(&security::TESTING_TLS12)?;
+        let mut pair = TestPair::from_config(&config);
+
+        for conn in [&mut pair.client, &mut pair.server] {
+            assert_ktls_error(
+                conn.enable_ktls_send().unwrap_err(),
+                KtlsError::HandshakeNotComplete,
+            );
+            assert_ktls_error(
+                conn.enable_ktls_recv().unwrap_err(),
+                KtlsError::HandshakeNotComplete,
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn custom_io_not_supported() -> Result<(), Error> {
+        let config = build_config(&security::TESTING_TLS12)?;
+        let mut pair = TestPair::from_config(&config);
+        pair.handshake()?;
+
+        for conn in [&mut pair.client, &mut pair.server] {
+            assert_ktls_error(
+                conn.enable_ktls_send().unwrap_err(),
+                KtlsError::ManagedIoRequired,
+            );
+            assert_ktls_error(
+                conn.enable_ktls_recv().unwrap_err(),
+                KtlsError::ManagedIoRequired,
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn sendfile_requires_ktls() -> Result<(), Error> {
+        let config = build_config(&security::TESTING_TLS12)?;
+        let mut pair = TestPair::from_config(&config);
+        pair.handshake()?;
+
+        let file =
+            std::fs::File::open(concat!(env!("CARGO_MANIFEST_DIR"), "/src/ktls.rs")).unwrap();
+        let result = pair.client.poll_sendfile(file.as_raw_fd(), 0, 10);
+        assert!(matches!(result, Poll::Ready(Err(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn unrelated_error() {
+        let error = Error::bindings(ErrorType::UsageError, "name", "message");
+        assert_eq!(error.ktls_error(), None);
+    }
+}
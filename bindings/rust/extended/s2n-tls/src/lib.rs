@@ -18,16 +18,23 @@ pub mod callbacks;
 pub mod cert_authorities;
 pub mod cert_chain;
 pub mod client_hello;
+mod codec;
 pub mod config;
 pub mod connection;
+#[cfg(feature = "unstable-crl")]
+pub mod crl;
 pub mod enums;
 #[cfg(feature = "unstable-fingerprint")]
 pub mod fingerprint;
 pub mod init;
+#[cfg(feature = "unstable-ktls")]
+pub mod ktls;
+pub mod ocsp;
 pub mod pool;
 pub mod psk;
 #[cfg(feature = "unstable-renegotiate")]
 pub mod renegotiate;
+pub mod sct;
 pub mod security;
 
 pub use s2n_tls_sys as ffi;
@@ -0,0 +1,30 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for OCSP stapling.
+//!
+//! Clients request a stapled OCSP response with
+//! [config::Builder::enable_ocsp](`crate::config::Builder::enable_ocsp()`).
+//! s2n-tls validates received responses unless disabled with
+//! [config::Builder::set_check_stapled_ocsp_response](`crate::config::Builder::set_check_stapled_ocsp_response()`).
+//!
+//! Certificates can require that their OCSP response is always stapled with the
+//! TLS Feature extension from [RFC 7633](https://www.rfc-editor.org/rfc/rfc7633),
+//! also known as "must-staple". s2n-tls does not enforce the extension itself:
+//! clients that want it enforced must enable
+//! [config::Builder::set_ocsp_must_staple](`crate::config::Builder::set_ocsp_must_staple()`),
+//! which requires the `unstable-crl` feature.
+//!
+//! Servers can keep their stapled responses fresh with an [`OcspStapler`].
+
+#[cfg(feature = "unstable-crl")]
+mod must_staple;
+mod stapler;
+pub use stapler::*;
+
+// DER tags
+const INTEGER: u8 = 0x02;
+const OCTET_STRING: u8 = 0x04;
+const OBJECT_IDENTIFIER: u8 = 0x06;
+const SEQUENCE: u8 = 0x30;
+const EXPLICIT_0: u8 = 0xa0;
@@ -0,0 +1,325 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Client-side enforcement of the must-staple TLS Feature extension.
+//!
+//! Enforcement happens while s2n-tls validates the server's certificates,
+//! so a missing staple rejects the certificates and aborts the handshake.
+
+use super::{EXPLICIT_0, INTEGER, OBJECT_IDENTIFIER, OCTET_STRING, SEQUENCE};
+use crate::{
+    codec::Reader,
+    connection::Connection,
+    enums::Mode,
+    error::{Error, ErrorType},
+};
+
+/// The DER encoding of id-pe-tlsfeature (1.3.6.1.5.5.7.1.24).
+const TLS_FEATURE_OID: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x01, 0x18];
+/// The TLS Feature value indicating the status_request extension.
+const STATUS_REQUEST: u8 = 5;
+
+// DER tags
+const BOOLEAN: u8 = 0x01;
+const EXPLICIT_3: u8 = 0xa3;
+const IMPLICIT_1: u8 = 0x81;
+const IMPLICIT_2: u8 = 0x82;
+
+const MISSING_STAPLE: Error = Error::bindings(
+    ErrorType::ProtocolError,
+    "OcspMustStapleError",
+    "peer certificate requires a stapled OCSP response, but none was received",
+);
+
+const MALFORMED_FEATURE: Error = Error::bindings(
+    ErrorType::ProtocolError,
+    "OcspMustStapleError",
+    "peer certificate has a malformed TLS Feature extension",
+);
+
+/// Returns true if a DER-encoded certificate has a TLS Feature extension
+/// that includes status_request.
+fn requires_staple(cert: &[u8]) -> Result<bool, Error> {
+    let mut tbs_certificate = Reader(cert).der(SEQUENCE)?.der(SEQUENCE)?;
+    // version
+    tbs_certificate.der_optional(EXPLICIT_0)?;
+    // serialNumber, signature, issuer, validity, subject, subjectPublicKeyInfo
+    for _ in 0..6 {
+        tbs_certificate.der_skip()?;
+    }
+    // issuerUniqueID and subjectUniqueID
+    tbs_certificate.der_optional(IMPLICIT_1)?;
+    tbs_certificate.der_optional(IMPLICIT_2)?;
+    // Certificates without extensions, including v1 certificates,
+    // can't require a staple.
+    let mut extensions = match tbs_certificate.der_optional(EXPLICIT_3)? {
+        Some(mut extensions) => extensions.der(SEQUENCE)?,
+        None => return Ok(false),
+    };
+
+    while !extensions.is_empty() {
+        let mut extension = extensions.der(SEQUENCE)?;
+        let oid = extension.der(OBJECT_IDENTIFIER)?;
+        // critical
+        extension.der_optional(BOOLEAN)?;
+        let value = extension.der(OCTET_STRING)?;
+        extension.finish(())?;
+        if oid.0 == TLS_FEATURE_OID {
+            return includes_status_request(value).map_err(|_| MALFORMED_FEATURE);
+        }
+    }
+    Ok(false)
+}
+
+/// Returns true if a TLS Feature extension value, a DER-encoded
+/// `SEQUENCE OF INTEGER`, includes status_request.
+fn includes_status_request(mut value: Reader) -> Result<bool, Error> {
+    let features = value.der(SEQUENCE)?.items(|feature| feature.der(INTEGER))?;
+    let includes = features.iter().any(|f| f.0 == [STATUS_REQUEST]);
+    value.finish(includes)
+}
+
+impl Connection {
+    /// Fails if must-staple is enforced, the peer's certificate requires
+    /// a stapled OCSP response, and no response was stapled.
+    ///
+    /// Called from the cert validation callback: the peer's chain has been
+    /// verified, and whether a response was stapled is already known.
+    /// Responses that were stapled are validated by s2n-tls later in the handshake.
+    pub(crate) fn check_must_staple(&self) -> Result<(), Error> {
+        if self.mode() != Mode::Client || self.is_ocsp_stapled() {
+            return Ok(());
+        }
+        let enforced = self
+            .config()
+            .map(|config| config.context().ocsp_must_staple)
+            .unwrap_or(false);
+        if !enforced {
+            return Ok(());
+        }
+
+        let chain = self.peer_cert_chain()?;
+        let leaf = match chain.iter().next() {
+            Some(leaf) => leaf?,
+            None => return Ok(()),
+        };
+        if requires_staple(leaf.der()?)? {
+            return Err(MISSING_STAPLE);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        callbacks::{CertValidationCallbackSync, CertValidationInfo},
+        config::{self, Config},
+        enums::Version,
+        security::{Policy, DEFAULT_TLS13, TESTING_TLS12},
+        testing::{Counter, InsecureAcceptAllCertificatesHandler, TestPair},
+    };
+    use core::{task::Poll, time::Duration};
+    use openssl::{
+        asn1::{Asn1Object, Asn1OctetString, Asn1Time},
+        ec::{EcGroup, EcKey},
+        hash::MessageDigest,
+        nid::Nid,
+        pkey::PKey,
+        x509::{
+            extension::{BasicConstraints, KeyUsage},
+            X509Extension, X509NameBuilder, X509,
+        },
+    };
+
+    /// The X.509 extensions of a test certificate.
+    #[derive(Clone, Copy)]
+    enum Extensions {
+        Empty,
+        /// Extensions, but not the TLS Feature extension.
+        Unrelated,
+        MustStaple,
+    }
+
+    /// Generates a self-signed certificate and key with the given extensions.
+    fn test_cert(extensions: Extensions) -> Result<(X509, Vec<u8>), Box<dyn std::error::Error>> {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+        let key = PKey::from_ec_key(EcKey::generate(&group)?)?;
+
+        let mut name = X509NameBuilder::new()?;
+        name.append_entry_by_text("CN", "localhost")?;
+        let name = name.build();
+
+        let mut cert = X509::builder()?;
+        cert.set_version(2)?;
+        cert.set_subject_name(&name)?;
+        cert.set_issuer_name(&name)?;
+        cert.set_pubkey(&key)?;
+        cert.set_not_before(&*Asn1Time::days_from_now(0)?)?;
+        cert.set_not_after(&*Asn1Time::days_from_now(1)?)?;
+        match extensions {
+            Extensions::Empty => {}
+            Extensions::Unrelated => {
+                cert.append_extension(BasicConstraints::new().critical().build()?)?;
+                cert.append_extension(KeyUsage::new().digital_signature().build()?)?;
+            }
+            Extensions::MustStaple => {
+                let oid = Asn1Object::from_str("1.3.6.1.5.5.7.1.24")?;
+                // SEQUENCE { INTEGER 5 }
+                let value =
+                    Asn1OctetString::new_from_bytes(&[0x30, 0x03, 0x02, 0x01, STATUS_REQUEST])?;
+                cert.append_extension(X509Extension::new_from_der(&oid, false, &value)?)?;
+            }
+        }
+        cert.sign(&key, MessageDigest::sha256())?;
+
+        Ok((cert.build(), key.private_key_to_pem_pkcs8()?))
+    }
+
+    const POLICIES: [(&Policy, Version); 2] = [
+        (&TESTING_TLS12, Version::TLS12),
+        (&DEFAULT_TLS13, Version::TLS13),
+    ];
+
+    fn config_builders(
+        policy: &Policy,
+        extensions: Extensions,
+        staple: Option<&[u8]>,
+    ) -> Result<(config::Builder, config::Builder), Box<dyn std::error::Error>> {
+        let (cert, key) = test_cert(extensions)?;
+        let cert = cert.to_pem()?;
+
+        let mut server = config::Builder::new();
+        server.set_security_policy(policy)?.load_pem(&cert, &key)?;
+        if let Some(staple) = staple {
+            server.set_ocsp_data(staple)?;
+        }
+
+        let mut client = config::Builder::new();
+        client
+            .set_security_policy(policy)?
+            .trust_pem(&cert)?
+            .set_verify_host_callback(InsecureAcceptAllCertificatesHandler {})?
+            .enable_ocsp()?
+            // The test staple is not a real OCSP response
+            .set_check_stapled_ocsp_response(false)?
+            .set_ocsp_must_staple(true)?;
+
+        Ok((client, server))
+    }
+
+    fn configs(
+        policy: &Policy,
+        extensions: Extensions,
+        staple: Option<&[u8]>,
+    ) -> Result<(Config, Config), Box<dyn std::error::Error>> {
+        let (client, server) = config_builders(policy, extensions, staple)?;
+        Ok((client.build()?, server.build()?))
+    }
+
+    #[test]
+    fn must_staple_without_staple() -> Result<(), Box<dyn std::error::Error>> {
+        for (policy, version) in POLICIES {
+            let (client, server) = configs(policy, Extensions::MustStaple, None)?;
+            let mut pair = TestPair::from_configs(&client, &server);
+
+            // The certificate is rejected during the handshake,
+            // so s2n-tls closes the connection and will send an alert.
+            let err = pair.handshake().unwrap_err();
+            assert_eq!(err.name(), "S2N_ERR_CERT_REJECTED");
+            assert_eq!(pair.client.actual_protocol_version()?, version);
+            assert!(!pair.client.is_ocsp_stapled());
+            assert!(pair.client.remaining_blinding_delay()? > Duration::ZERO);
+            assert!(matches!(
+                pair.client.poll_send(b"data"),
+                Poll::Ready(Err(_))
+            ));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn must_staple_with_staple() -> Result<(), Box<dyn std::error::Error>> {
+        const STAPLE: &[u8] = b"not really an OCSP response";
+        for (policy, version) in POLICIES {
+            let (client, server) = configs(policy, Extensions::MustStaple, Some(STAPLE))?;
+            let mut pair = TestPair::from_configs(&client, &server);
+
+            pair.handshake()?;
+            assert_eq!(pair.client.actual_protocol_version()?, version);
+            assert!(pair.client.is_ocsp_stapled());
+            assert_eq!(pair.client.ocsp_response(), Some(STAPLE));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn must_staple_with_validation_callback() -> Result<(), Box<dyn std::error::Error>> {
+        struct AcceptCallback(Counter);
+        impl CertValidationCallbackSync for AcceptCallback {
+            fn handle_validation(
+                &self,
+                _conn: &mut Connection,
+                _info: &mut CertValidationInfo,
+            ) -> Result<bool, Error> {
+                self.0.increment();
+                Ok(true)
+            }
+        }
+
+        const STAPLE: &[u8] = b"not really an OCSP response";
+        for staple in [None, Some(STAPLE)] {
+            let counter = Counter::default();
+            let (mut client, server) =
+                config_builders(&DEFAULT_TLS13, Extensions::MustStaple, staple)?;
+            client.set_cert_validation_callback_sync(AcceptCallback(counter.clone()))?;
+            let (client, server) = (client.build()?, server.build()?);
+            let mut pair = TestPair::from_configs(&client, &server);
+
+            // The application's callback only sees certificates with their staple
+            if staple.is_some() {
+                pair.handshake()?;
+                assert_eq!(counter.count(), 1);
+            } else {
+                let err = pair.handshake().unwrap_err();
+                assert_eq!(err.name(), "S2N_ERR_CERT_REJECTED");
+                assert_eq!(counter.count(), 0);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn staple_not_required() -> Result<(), Box<dyn std::error::Error>> {
+        for extensions in [Extensions::Empty, Extensions::Unrelated] {
+            let (client, server) = configs(&DEFAULT_TLS13, extensions, None)?;
+            let mut pair = TestPair::from_configs(&client, &server);
+
+            pair.handshake()?;
+            assert!(!pair.client.is_ocsp_stapled());
+            assert_eq!(pair.client.ocsp_response(), None);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn requires_staple_decoding() -> Result<(), Box<dyn std::error::Error>> {
+        let der = |extensions| -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+            Ok(test_cert(extensions)?.0.to_der()?)
+        };
+        assert!(!requires_staple(&der(Extensions::Empty)?)?);
+        assert!(!requires_staple(&der(Extensions::Unrelated)?)?);
+        assert!(requires_staple(&der(Extensions::MustStaple)?)?);
+
+        // TLS Feature extension values are decoded
+        assert!(!includes_status_request(Reader(&[0x30, 0x00]))?);
+        assert!(!includes_status_request(Reader(&[
+            0x30, 0x03, 0x02, 0x01, 17
+        ]))?);
+        assert!(includes_status_request(Reader(&[0x30, 0x03, 0x02, 0x01])).is_err());
+
+        assert!(requires_staple(&[0x30, 0x00]).is_err());
+        Ok(())
+    }
+}
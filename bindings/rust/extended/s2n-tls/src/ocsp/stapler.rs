@@ -0,0 +1,678 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Server-side refreshing of stapled OCSP responses.
+//!
+//! OCSP data set with
+//! [cert_chain::Builder::set_ocsp_data](`crate::cert_chain::Builder::set_ocsp_data()`)
+//! is fixed once the chain is built, and eventually expires. [`OcspStapler`]
+//! fetches a new response and builds a new chain with it whenever it is
+//! ticked with [`OcspStapler::refresh()`] and the current response is due.
+//!
+//! The stapler doesn't start a thread or task: applications drive it from
+//! their own scheduler, and load each new chain into new configs. For example:
+//! ```no_run
+//! use s2n_tls::{config, ocsp::OcspStapler};
+//!
+//! fn drive(mut stapler: OcspStapler) {
+//!     loop {
+//!         match stapler.refresh() {
+//!             Ok(true) => {
+//!                 let config = stapler.build_config(config::Builder::new());
+//!                 // swap the new config in for new connections
+//!             }
+//!             Ok(false) => {}
+//!             // the previous chain is still valid until its nextUpdate
+//!             Err(_) => {}
+//!         }
+//!         std::thread::sleep(stapler.time_until_refresh());
+//!     }
+//! }
+//! ```
+
+use super::{EXPLICIT_0, INTEGER, OBJECT_IDENTIFIER, OCTET_STRING, SEQUENCE};
+use crate::{
+    callbacks::WallClock,
+    cert_chain::{self, CertificateChain},
+    codec::Reader,
+    config::{self, Config},
+    error::{Error, ErrorType},
+};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// DER tags
+const ENUMERATED: u8 = 0x0a;
+const GENERALIZED_TIME: u8 = 0x18;
+const CERT_STATUS_GOOD: u8 = 0x80;
+
+/// The DER encoding of id-pkix-ocsp-basic (1.3.6.1.5.5.7.48.1.1).
+const OCSP_BASIC: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x30, 0x01, 0x01];
+/// The "successful" OCSPResponseStatus.
+const SUCCESSFUL: &[u8] = &[0];
+
+/// How long before a response's nextUpdate it should be refreshed, by default.
+const DEFAULT_REFRESH_MARGIN: Duration = Duration::from_secs(60 * 60);
+/// How long to wait before retrying after a refresh fails.
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+const fn response_error(message: &'static str) -> Error {
+    Error::bindings(ErrorType::ProtocolError, "OcspResponseError", message)
+}
+
+/// A trait for fetching OCSP responses for a certificate chain.
+///
+/// Use in conjunction with [`OcspStapler`].
+pub trait OcspFetcher: 'static + Send + Sync {
+    /// Fetch a DER-encoded OCSP response for the leaf certificate of `chain`.
+    ///
+    /// Building an OCSP request requires the leaf's issuer, so the issuer
+    /// should be included in the chain.
+    fn fetch(&self, chain: &CertificateChain) -> Result<Vec<u8>, Error>;
+}
+
+/// The fields of an OCSP response that the stapler checks.
+struct ResponseStatus {
+    good: bool,
+    this_update: SystemTime,
+    next_update: Option<SystemTime>,
+}
+
+/// Keeps the OCSP response stapled to a certificate chain up to date.
+///
+/// The stapler doesn't run on its own: call [`OcspStapler::refresh()`]
+/// periodically, for example after [`OcspStapler::time_until_refresh()`],
+/// then load the new [`OcspStapler::chain()`] into a new config.
+/// See [the module documentation](self) for an example.
+///
+/// Responses are only checked for freshness and that they apply to the
+/// leaf certificate. Their signatures are not validated, because clients
+/// validate stapled responses themselves.
+pub struct OcspStapler {
+    chain_pem: Vec<u8>,
+    key_pem: Vec<u8>,
+    fetcher: Box<dyn OcspFetcher>,
+    chain: CertificateChain<'static>,
+    next_update: Option<SystemTime>,
+    refresh_margin: Duration,
+    wall_clock: Option<Box<dyn WallClock>>,
+    last_failure: Option<SystemTime>,
+}
+
+impl OcspStapler {
+    /// Create a stapler for the chain and private key.
+    ///
+    /// The PEM data is kept so that the chain can be rebuilt with each new
+    /// response: built chains may be shared between configs, so can't be modified.
+    ///
+    /// The initial chain has no stapled response until the first refresh.
+    pub fn new<T: 'static + OcspFetcher>(
+        chain_pem: &[u8],
+        key_pem: &[u8],
+        fetcher: T,
+    ) -> Result<Self, Error> {
+        let chain = {
+            let mut builder = cert_chain::Builder::new()?;
+            builder.load_pem(chain_pem, key_pem)?;
+            builder.build()?
+        };
+        Ok(Self {
+            chain_pem: chain_pem.to_vec(),
+            key_pem: key_pem.to_vec(),
+            fetcher: Box::new(fetcher),
+            chain,
+            next_update: None,
+            refresh_margin: DEFAULT_REFRESH_MARGIN,
+            wall_clock: None,
+            last_failure: None,
+        })
+    }
+
+    /// Sets how long before a response's nextUpdate time a new response is fetched.
+    ///
+    /// Defaults to one hour.
+    pub fn set_refresh_margin(&mut self, margin: Duration) -> &mut Self {
+        self.refresh_margin = margin;
+        self
+    }
+
+    /// Sets the clock used to decide whether responses are due for a refresh,
+    /// and whether new responses are valid.
+    ///
+    /// Defaults to the system time.
+    pub fn set_wall_clock<T: 'static + WallClock>(&mut self, clock: T) -> &mut Self {
+        self.wall_clock = Some(Box::new(clock));
+        self
+    }
+
+    fn now(&self) -> SystemTime {
+        match &self.wall_clock {
+            Some(clock) => UNIX_EPOCH + clock.get_time_since_epoch(),
+            None => SystemTime::now(),
+        }
+    }
+
+    /// The chain with the most recent valid response stapled.
+    pub fn chain(&self) -> CertificateChain<'static> {
+        self.chain.clone()
+    }
+
+    /// The nextUpdate time of the stapled response,
+    /// or `None` if no response has been stapled yet.
+    pub fn next_update(&self) -> Option<SystemTime> {
+        self.next_update
+    }
+
+    /// When [`OcspStapler::refresh()`] will next fetch a response,
+    /// or `None` if it should be called immediately.
+    pub fn next_refresh(&self) -> Option<SystemTime> {
+        self.next_update
+            .and_then(|next_update| next_update.checked_sub(self.refresh_margin))
+    }
+
+    /// How long to wait before calling [`OcspStapler::refresh()`] again.
+    ///
+    /// If the last refresh failed, for example because the responder was
+    /// unavailable or returned a stale response, retries a minute after the failure.
+    pub fn time_until_refresh(&self) -> Duration {
+        let retry = match self.last_failure {
+            Some(last_failure) => Some(last_failure + RETRY_INTERVAL),
+            None => self.next_refresh(),
+        };
+        retry
+            .and_then(|retry| retry.duration_since(self.now()).ok())
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Fetches and staples a new response if the current response is missing
+    /// or close to expiring.
+    ///
+    /// Safe to call as often as needed: if the current response isn't due
+    /// for a refresh, nothing is fetched. Returns true if a new chain was built.
+    /// Invalid or expired responses are rejected with an error, and the previous
+    /// chain is kept.
+    pub fn refresh(&mut self) -> Result<bool, Error> {
+        let now = self.now();
+        if matches!(self.next_refresh(), Some(next_refresh) if now < next_refresh) {
+            return Ok(false);
+        }
+
+        match self.fetch_chain(now) {
+            Ok((chain, next_update)) => {
+                self.chain = chain;
+                self.next_update = Some(next_update);
+                self.last_failure = None;
+                Ok(true)
+            }
+            Err(err) => {
+                self.last_failure = Some(now);
+                Err(err)
+            }
+        }
+    }
+
+    /// Fetches a new response, and builds a chain with it if it is valid at `now`.
+    fn fetch_chain(
+        &self,
+        now: SystemTime,
+    ) -> Result<(CertificateChain<'static>, SystemTime), Error> {
+        let response = self.fetcher.fetch(&self.chain)?;
+        let serial = match self.chain.iter().next() {
+            Some(leaf) => leaf_serial(leaf?.der()?)?.to_vec(),
+            None => return Err(Error::INVALID_INPUT),
+        };
+        let status = decode_response(&response, &serial)?;
+        if !status.good {
+            return Err(response_error("certificate status is not good"));
+        }
+        if status.this_update > now {
+            return Err(response_error("response is not valid yet"));
+        }
+        let next_update = match status.next_update {
+            Some(next_update) if next_update > now => next_update,
+            Some(_) => return Err(response_error("response has expired")),
+            None => return Err(response_error("response has no nextUpdate")),
+        };
+
+        let mut builder = cert_chain::Builder::new()?;
+        builder
+            .load_pem(&self.chain_pem, &self.key_pem)?
+            .set_ocsp_data(&response)?;
+        Ok((builder.build()?, next_update))
+    }
+
+    /// Loads the current chain into `builder` and builds the config.
+    pub fn build_config(&self, mut builder: config::Builder) -> Result<Config, Error> {
+        builder.load_chain(self.chain())?;
+        builder.build()
+    }
+}
+
+/// Reads the serial number from a DER-encoded certificate.
+fn leaf_serial(der: &[u8]) -> Result<&[u8], Error> {
+    let mut tbs_certificate = Reader(der).der(SEQUENCE)?.der(SEQUENCE)?;
+    // version
+    tbs_certificate.der_optional(EXPLICIT_0)?;
+    Ok(tbs_certificate.der(INTEGER)?.0)
+}
+
+/// Decodes the status of the certificate with `serial` from a DER-encoded
+/// OCSPResponse, as defined in [RFC 6960](https://www.rfc-editor.org/rfc/rfc6960#section-4.2.1).
+fn decode_response(response: &[u8], serial: &[u8]) -> Result<ResponseStatus, Error> {
+    let mut reader = Reader(response);
+    let mut ocsp_response = reader.der(SEQUENCE)?;
+    reader.finish(())?;
+    if ocsp_response.der(ENUMERATED)?.0 != SUCCESSFUL {
+        return Err(response_error(
+            "responder did not return a successful response",
+        ));
+    }
+    let mut response_bytes = ocsp_response.der(EXPLICIT_0)?.der(SEQUENCE)?;
+    if response_bytes.der(OBJECT_IDENTIFIER)?.0 != OCSP_BASIC {
+        return Err(response_error("response is not a basic OCSP response"));
+    }
+
+    let mut basic_response = response_bytes.der(OCTET_STRING)?.der(SEQUENCE)?;
+    let mut response_data = basic_response.der(SEQUENCE)?;
+    // version
+    response_data.der_optional(EXPLICIT_0)?;
+    // responderID
+    response_data.der_skip()?;
+    // producedAt
+    response_data.der(GENERALIZED_TIME)?;
+
+    let responses = response_data.der(SEQUENCE)?.items(|single_response| {
+        let mut single_response = single_response.der(SEQUENCE)?;
+        let mut cert_id = single_response.der(SEQUENCE)?;
+        // hashAlgorithm, issuerNameHash, issuerKeyHash
+        cert_id.der(SEQUENCE)?;
+        cert_id.der(OCTET_STRING)?;
+        cert_id.der(OCTET_STRING)?;
+        let serial_number = cert_id.der(INTEGER)?.0;
+
+        let good = single_response.der_optional(CERT_STATUS_GOOD)?.is_some();
+        if !good {
+            // revoked or unknown
+            single_response.der_skip()?;
+        }
+        let this_update = generalized_time(single_response.der(GENERALIZED_TIME)?.0)?;
+        let next_update = match single_response.der_optional(EXPLICIT_0)? {
+            Some(mut next_update) => Some(generalized_time(next_update.der(GENERALIZED_TIME)?.0)?),
+            None => None,
+        };
+        let status = ResponseStatus {
+            good,
+            this_update,
+            next_update,
+        };
+        Ok((serial_number, status))
+    })?;
+
+    responses
+        .into_iter()
+        .find(|(serial_number, _)| *serial_number == serial)
+        .map(|(_, status)| status)
+        .ok_or(response_error("response does not include the certificate"))
+}
+
+/// Decodes a DER GeneralizedTime, in the form `YYYYMMDDHHMMSS[.fff]Z`.
+fn generalized_time(time: &[u8]) -> Result<SystemTime, Error> {
+    const MALFORMED: Error = response_error("malformed GeneralizedTime");
+
+    let digits = |range: std::ops::Range<usize>| -> Result<u64, Error> {
+        let bytes = time.get(range).ok_or(MALFORMED)?;
+        bytes.iter().try_fold(0, |value, byte| match byte {
+            b'0'..=b'9' => Ok(value * 10 + u64::from(byte - b'0')),
+            _ => Err(MALFORMED),
+        })
+    };
+    let (year, month, day) = (digits(0..4)?, digits(4..6)?, digits(6..8)?);
+    let (hour, minute, second) = (digits(8..10)?, digits(10..12)?, digits(12..14)?);
+    // Fractional seconds are allowed, but too small to matter for freshness.
+    match &time[14..] {
+        [b'Z'] => {}
+        [b'.', fraction @ .., b'Z'] if fraction.iter().all(u8::is_ascii_digit) => {}
+        _ => return Err(MALFORMED),
+    }
+    if year < 1970 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(MALFORMED);
+    }
+    if hour > 23 || minute > 59 || second > 59 {
+        return Err(MALFORMED);
+    }
+
+    // Days since the unix epoch, from http://howardhinnant.github.io/date_algorithms.html
+    let (year, month) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+
+    let seconds = days * 86400 + hour * 3600 + minute * 60 + second;
+    Ok(UNIX_EPOCH + Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        security::DEFAULT_TLS13,
+        testing::{CertKeyPair, Counter, InsecureAcceptAllCertificatesHandler, TestPair},
+    };
+    use openssl::x509::X509;
+    use std::sync::{Arc, Mutex};
+
+    const PAST: &[u8] = b"20000101000000Z";
+    const FUTURE: &[u8] = b"29991231235959Z";
+
+    /// Encodes a DER element.
+    fn der(tag: u8, contents: &[u8]) -> Vec<u8> {
+        let mut element = vec![tag];
+        match contents.len() {
+            len @ 0..=0x7f => element.push(len as u8),
+            len @ 0x80..=0xff => element.extend_from_slice(&[0x81, len as u8]),
+            len => {
+                element.push(0x82);
+                element.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+        }
+        element.extend_from_slice(contents);
+        element
+    }
+
+    fn serial(keypair: &CertKeyPair) -> Vec<u8> {
+        let cert = X509::from_pem(keypair.cert()).unwrap();
+        let mut serial = cert.serial_number().to_bn().unwrap().to_vec();
+        // DER integers are signed
+        if serial[0] & 0x80 != 0 {
+            serial.insert(0, 0);
+        }
+        serial
+    }
+
+    /// Builds an unsigned OCSP response with a single certificate status.
+    fn ocsp_response(serial: &[u8], cert_status: &[u8], next_update: Option<&[u8]>) -> Vec<u8> {
+        let cert_id = [
+            der(
+                SEQUENCE,
+                &der(OBJECT_IDENTIFIER, &[0x2b, 0x0e, 0x03, 0x02, 0x1a]),
+            ),
+            der(OCTET_STRING, &[1; 20]),
+            der(OCTET_STRING, &[2; 20]),
+            der(INTEGER, serial),
+        ]
+        .concat();
+        let mut single_response = [
+            der(SEQUENCE, &cert_id),
+            cert_status.to_vec(),
+            der(GENERALIZED_TIME, PAST),
+        ]
+        .concat();
+        if let Some(next_update) = next_update {
+            single_response.extend(der(EXPLICIT_0, &der(GENERALIZED_TIME, next_update)));
+        }
+
+        let response_data = [
+            // responderID: byKey
+            der(0xa2, &der(OCTET_STRING, &[3; 20])),
+            der(GENERALIZED_TIME, PAST),
+            der(SEQUENCE, &der(SEQUENCE, &single_response)),
+        ]
+        .concat();
+        let basic_response = [
+            der(SEQUENCE, &response_data),
+            // sha256WithRSAEncryption, and a fake signature
+            der(
+                SEQUENCE,
+                &der(
+                    OBJECT_IDENTIFIER,
+                    &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b],
+                ),
+            ),
+            der(0x03, &[0, 4, 5, 6]),
+        ]
+        .concat();
+        let response_bytes = [
+            der(OBJECT_IDENTIFIER, OCSP_BASIC),
+            der(OCTET_STRING, &der(SEQUENCE, &basic_response)),
+        ]
+        .concat();
+        let response = [
+            der(ENUMERATED, SUCCESSFUL),
+            der(EXPLICIT_0, &der(SEQUENCE, &response_bytes)),
+        ]
+        .concat();
+        der(SEQUENCE, &response)
+    }
+
+    /// A stand-in for an OCSP responder, which returns the configured response.
+    #[derive(Clone, Default)]
+    struct TestResponder {
+        response: Arc<Mutex<Vec<u8>>>,
+        fetches: Counter,
+    }
+
+    impl TestResponder {
+        fn set_response(&self, response: Vec<u8>) {
+            *self.response.lock().unwrap() = response;
+        }
+    }
+
+    impl OcspFetcher for TestResponder {
+        fn fetch(&self, _chain: &CertificateChain) -> Result<Vec<u8>, Error> {
+            self.fetches.increment();
+            Ok(self.response.lock().unwrap().clone())
+        }
+    }
+
+    #[test]
+    fn refresh() -> Result<(), Error> {
+        let keypair = CertKeyPair::default();
+        let response = ocsp_response(&serial(&keypair), &der(CERT_STATUS_GOOD, &[]), Some(FUTURE));
+        let responder = TestResponder::default();
+        responder.set_response(response.clone());
+
+        let mut stapler = OcspStapler::new(keypair.cert(), keypair.key(), responder.clone())?;
+        assert_eq!(stapler.next_refresh(), None);
+        assert!(stapler.refresh()?);
+        assert_eq!(stapler.next_update(), Some(generalized_time(FUTURE)?));
+        assert_eq!(
+            stapler.next_refresh(),
+            Some(generalized_time(FUTURE)? - DEFAULT_REFRESH_MARGIN)
+        );
+
+        // The response is still fresh, so isn't fetched again
+        assert!(!stapler.refresh()?);
+        assert_eq!(responder.fetches.count(), 1);
+
+        let server = {
+            let mut builder = config::Builder::new();
+            builder.set_security_policy(&DEFAULT_TLS13)?;
+            stapler.build_config(builder)?
+        };
+        let client = {
+            let mut builder = config::Builder::new();
+            builder
+                .set_security_policy(&DEFAULT_TLS13)?
+                .trust_pem(keypair.cert())?
+                .set_verify_host_callback(InsecureAcceptAllCertificatesHandler {})?
+                .enable_ocsp()?
+                // The test response is not signed
+                .set_check_stapled_ocsp_response(false)?;
+            builder.build()?
+        };
+        let mut pair = TestPair::from_configs(&client, &server);
+        pair.handshake()?;
+        assert_eq!(pair.client.ocsp_response(), Some(response.as_slice()));
+        Ok(())
+    }
+
+    /// A clock that tests can move forward.
+    #[derive(Clone)]
+    struct TestClock(Arc<Mutex<SystemTime>>);
+
+    impl TestClock {
+        fn set(&self, time: &[u8]) {
+            *self.0.lock().unwrap() = generalized_time(time).unwrap();
+        }
+    }
+
+    impl WallClock for TestClock {
+        fn get_time_since_epoch(&self) -> Duration {
+            self.0.lock().unwrap().duration_since(UNIX_EPOCH).unwrap()
+        }
+    }
+
+    #[test]
+    fn refresh_past_next_update() -> Result<(), Error> {
+        const HOUR: Duration = Duration::from_secs(60 * 60);
+
+        let keypair = CertKeyPair::default();
+        let serial = serial(&keypair);
+        let good = der(CERT_STATUS_GOOD, &[]);
+        let responder = TestResponder::default();
+        let clock = TestClock(Arc::new(Mutex::new(UNIX_EPOCH)));
+
+        let mut stapler = OcspStapler::new(keypair.cert(), keypair.key(), responder.clone())?;
+        stapler.set_wall_clock(clock.clone());
+
+        clock.set(b"20300101000000Z");
+        responder.set_response(ocsp_response(&serial, &good, Some(b"20300102000000Z")));
+        assert!(stapler.refresh()?);
+        assert_eq!(stapler.time_until_refresh(), HOUR * 23);
+
+        // Before the refresh margin, nothing is fetched
+        clock.set(b"20300101220000Z");
+        assert!(!stapler.refresh()?);
+        assert_eq!(stapler.time_until_refresh(), HOUR);
+        assert_eq!(responder.fetches.count(), 1);
+
+        // Within the refresh margin, a new response is fetched
+        clock.set(b"20300101233000Z");
+        assert_eq!(stapler.time_until_refresh(), Duration::ZERO);
+        responder.set_response(ocsp_response(&serial, &good, Some(b"20300103000000Z")));
+        assert!(stapler.refresh()?);
+        assert_eq!(
+            stapler.next_update(),
+            Some(generalized_time(b"20300103000000Z")?)
+        );
+        assert_eq!(responder.fetches.count(), 2);
+
+        // Past nextUpdate, the responder's stale response is rejected
+        // and the previous chain is kept
+        let chain = stapler.chain();
+        clock.set(b"20300104000000Z");
+        let err = stapler.refresh().unwrap_err();
+        assert_eq!(err.kind(), ErrorType::ProtocolError);
+        assert_eq!(stapler.chain().as_ptr(), chain.as_ptr());
+        assert_eq!(stapler.time_until_refresh(), RETRY_INTERVAL);
+
+        // Retries back off from the last failure
+        clock.set(b"20300104000030Z");
+        assert_eq!(stapler.time_until_refresh(), RETRY_INTERVAL / 2);
+        assert!(stapler.refresh().is_err());
+        assert_eq!(stapler.time_until_refresh(), RETRY_INTERVAL);
+
+        // Refreshing keeps retrying until a fresh response is available
+        clock.set(b"20300104000130Z");
+        assert_eq!(stapler.time_until_refresh(), Duration::ZERO);
+        responder.set_response(ocsp_response(&serial, &good, Some(b"20300105000000Z")));
+        assert!(stapler.refresh()?);
+        assert_eq!(
+            stapler.next_update(),
+            Some(generalized_time(b"20300105000000Z")?)
+        );
+        assert_eq!(responder.fetches.count(), 5);
+        assert_eq!(
+            stapler.time_until_refresh(),
+            HOUR * 23 - Duration::from_secs(90)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn retry_without_response() -> Result<(), Error> {
+        let keypair = CertKeyPair::default();
+        let responder = TestResponder::default();
+        let clock = TestClock(Arc::new(Mutex::new(UNIX_EPOCH)));
+        let mut stapler = OcspStapler::new(keypair.cert(), keypair.key(), responder)?;
+        stapler.set_wall_clock(clock.clone());
+
+        clock.set(b"20300101000000Z");
+        assert_eq!(stapler.time_until_refresh(), Duration::ZERO);
+        assert!(stapler.refresh().is_err());
+        assert_eq!(stapler.time_until_refresh(), RETRY_INTERVAL);
+        Ok(())
+    }
+
+    #[test]
+    fn refresh_margin() -> Result<(), Error> {
+        let keypair = CertKeyPair::default();
+        let responder = TestResponder::default();
+        responder.set_response(ocsp_response(
+            &serial(&keypair),
+            &der(CERT_STATUS_GOOD, &[]),
+            Some(FUTURE),
+        ));
+
+        let mut stapler = OcspStapler::new(keypair.cert(), keypair.key(), responder.clone())?;
+        // A margin longer than the response's lifetime means every call refreshes
+        stapler.set_refresh_margin(Duration::from_secs(u32::MAX.into()) * 10);
+        assert!(stapler.refresh()?);
+        assert!(stapler.refresh()?);
+        assert_eq!(responder.fetches.count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_responses() -> Result<(), Error> {
+        let keypair = CertKeyPair::default();
+        let serial = serial(&keypair);
+        let good = der(CERT_STATUS_GOOD, &[]);
+        // revoked, with a revocationTime
+        let revoked = der(0xa1, &der(GENERALIZED_TIME, PAST));
+
+        let responses = [
+            ocsp_response(&serial, &good, Some(PAST)),
+            ocsp_response(&serial, &good, None),
+            ocsp_response(&serial, &revoked, Some(FUTURE)),
+            ocsp_response(&[1, 2, 3], &good, Some(FUTURE)),
+            // tryLater
+            der(SEQUENCE, &der(ENUMERATED, &[3])),
+            b"not an OCSP response".to_vec(),
+        ];
+
+        let responder = TestResponder::default();
+        let mut stapler = OcspStapler::new(keypair.cert(), keypair.key(), responder.clone())?;
+        for response in responses {
+            responder.set_response(response);
+            let err = stapler.refresh().unwrap_err();
+            assert_eq!(err.kind(), ErrorType::ProtocolError);
+            assert_eq!(stapler.next_update(), None);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn generalized_times() -> Result<(), Error> {
+        assert_eq!(generalized_time(b"19700101000000Z")?, UNIX_EPOCH);
+        let expected = UNIX_EPOCH + Duration::from_secs(951_827_696);
+        // leap day
+        assert_eq!(generalized_time(b"20000229123456Z")?, expected);
+        assert_eq!(generalized_time(b"20000229123456.789Z")?, expected);
+
+        for invalid in [
+            &b"20000229123456"[..],
+            b"2000022912345Z",
+            b"20001329123456Z",
+            b"2000022912345aZ",
+        ] {
+            assert!(generalized_time(invalid).is_err());
+        }
+        Ok(())
+    }
+}
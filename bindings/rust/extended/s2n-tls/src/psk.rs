@@ -89,6 +89,83 @@ impl Builder {
         Ok(self)
     }
 
+    /// Allow the PSK to be used for early data.
+    ///
+    /// `cipher_suite` is the IANA value of the cipher suite associated with the
+    /// PSK. Early data is only accepted if this matches the cipher suite
+    /// negotiated by the handshake.
+    ///
+    /// Corresponds to [s2n_psk_configure_early_data].
+    pub fn set_early_data(
+        &mut self,
+        max_early_data_size: u32,
+        cipher_suite: [u8; 2],
+    ) -> Result<&mut Self, crate::error::Error> {
+        unsafe {
+            s2n_psk_configure_early_data(
+                self.psk.ptr.as_ptr(),
+                max_early_data_size,
+                cipher_suite[0],
+                cipher_suite[1],
+            )
+            .into_result()
+        }?;
+        Ok(self)
+    }
+
+    /// Set the application protocol associated with the PSK.
+    ///
+    /// Early data is only accepted if this matches the application protocol
+    /// negotiated by the handshake.
+    ///
+    /// Corresponds to [s2n_psk_set_application_protocol].
+    pub fn set_application_protocol(
+        &mut self,
+        protocol: &[u8],
+    ) -> Result<&mut Self, crate::error::Error> {
+        let protocol_length = protocol.len().try_into().map_err(|_| {
+            Error::bindings(
+                ErrorType::UsageError,
+                "invalid psk application protocol",
+                "The application protocol must be no longer than u8::MAX",
+            )
+        })?;
+        unsafe {
+            s2n_psk_set_application_protocol(
+                self.psk.ptr.as_ptr(),
+                protocol.as_ptr(),
+                protocol_length,
+            )
+            .into_result()
+        }?;
+        Ok(self)
+    }
+
+    /// Set the early data context associated with the PSK.
+    ///
+    /// The context is available to the
+    /// [EarlyDataCallback](`crate::callbacks::EarlyDataCallback`) through
+    /// [OfferedEarlyData::context](`crate::callbacks::OfferedEarlyData::context()`).
+    ///
+    /// Corresponds to [s2n_psk_set_early_data_context].
+    pub fn set_early_data_context(
+        &mut self,
+        context: &[u8],
+    ) -> Result<&mut Self, crate::error::Error> {
+        let context_length = context.len().try_into().map_err(|_| {
+            Error::bindings(
+                ErrorType::UsageError,
+                "invalid psk early data context",
+                "The early data context must be no longer than u16::MAX",
+            )
+        })?;
+        unsafe {
+            s2n_psk_set_early_data_context(self.psk.ptr.as_ptr(), context.as_ptr(), context_length)
+                .into_result()
+        }?;
+        Ok(self)
+    }
+
     pub fn build(self) -> Result<Psk, crate::error::Error> {
         if !self.has_identity {
             Err(Error::bindings(
@@ -163,7 +240,14 @@ impl Drop for Psk {
 
 #[cfg(test)]
 mod tests {
-    use crate::{config::Config, error::ErrorSource, security::DEFAULT_TLS13, testing::TestPair};
+    use crate::{
+        config::Config,
+        enums::PskMode,
+        error::ErrorSource,
+        security::DEFAULT_TLS13,
+        testing::{config_builder, TestPair},
+    };
+    use std::time::SystemTime;
 
     use super::*;
 
@@ -242,4 +326,76 @@ mod tests {
         }
         Ok(())
     }
+
+    /// External PSK handshakes are abbreviated, but don't resume a session.
+    #[test]
+    fn psk_handshake_flags() -> Result<(), crate::error::Error> {
+        let psk = test_psk();
+        let mut config = Config::builder();
+        config.set_security_policy(&DEFAULT_TLS13)?;
+        let config = config.build()?;
+        let mut test_pair = TestPair::from_config(&config);
+        test_pair.client.append_psk(&psk)?;
+        test_pair.server.append_psk(&psk)?;
+        test_pair.handshake()?;
+
+        for peer in [test_pair.client, test_pair.server] {
+            let flags = peer.handshake_flags()?;
+            assert!(flags.is_abbreviated());
+            assert!(!flags.is_full_handshake());
+            assert!(!peer.resumed());
+        }
+        Ok(())
+    }
+
+    /// The same config can be used for both resumption and external PSKs,
+    /// by overriding the PSK mode on individual connections.
+    #[test]
+    fn psk_mode_per_connection() -> Result<(), crate::error::Error> {
+        let config = {
+            let mut config = config_builder(&DEFAULT_TLS13)?;
+            config
+                .add_session_ticket_key(b"keyname", &[0; 16], SystemTime::now())?
+                .enable_session_tickets(true)?
+                .set_psk_mode(PskMode::Resumption)?;
+            config.build()?
+        };
+
+        // Full handshake to establish a session ticket
+        let mut pair = TestPair::from_config(&config);
+        pair.handshake()?;
+        // Read the post-handshake session ticket message
+        assert!(pair.client.poll_recv(&mut [0]).is_pending());
+        assert_eq!(pair.client.negotiated_psk_mode()?, None);
+        let mut ticket = vec![0; pair.client.session_ticket_length()?];
+        pair.client.session_ticket(&mut ticket)?;
+
+        let mut resumed = TestPair::from_config(&config);
+        resumed.client.set_session_ticket(&ticket)?;
+        resumed.handshake()?;
+
+        let psk = test_psk();
+        let mut external = TestPair::from_config(&config);
+        for conn in [&mut external.client, &mut external.server] {
+            conn.set_psk_mode(PskMode::External)?.append_psk(&psk)?;
+        }
+        external.handshake()?;
+
+        for conn in [&resumed.client, &resumed.server] {
+            assert_eq!(conn.negotiated_psk_mode()?, Some(PskMode::Resumption));
+        }
+        for conn in [&external.client, &external.server] {
+            assert_eq!(conn.negotiated_psk_mode()?, Some(PskMode::External));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn psk_mode_conflict() -> Result<(), crate::error::Error> {
+        let mut conn = crate::connection::Connection::new_client();
+        conn.append_psk(&test_psk())?;
+        assert!(conn.set_psk_mode(PskMode::Resumption).is_err());
+        assert!(conn.set_psk_mode(PskMode::External).is_ok());
+        Ok(())
+    }
 }
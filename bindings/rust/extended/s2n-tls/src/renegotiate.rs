@@ -197,7 +197,8 @@ impl Connection {
     ///
     /// Corresponds to [s2n_renegotiate_wipe].
     /// The Rust equivalent of the listed connection-specific methods that are NOT wiped are:
-    ///  - Methods to set the file descriptors: not currently supported by rust bindings
+    ///  - Methods to set the file descriptors: `Connection::set_fd()`, only available
+    ///    with the `unstable-ktls` feature
     ///  - Methods to set the send callback:
     ///    [Connection::set_send_callback()], [Connection::set_send_context()]
     ///  - Methods to set the recv callback:
@@ -318,6 +319,26 @@ impl Connection {
         result
     }
 
+    pub(crate) fn poll_sendv_raw(
+        &mut self,
+        bufs_ptr: *const libc::iovec,
+        count: isize,
+    ) -> Poll<Result<usize, Error>> {
+        if self.is_renegotiating() {
+            return Ready(Err(Error::bindings(
+                ErrorType::Blocked,
+                "RenegotiateError",
+                "Cannot send application data while renegotiating",
+            )));
+        }
+        let mut blocked = s2n_blocked_status::NOT_BLOCKED;
+        let result =
+            unsafe { s2n_sendv_with_offset(self.as_ptr(), bufs_ptr, count, 0, &mut blocked) }
+                .into_poll();
+        self.renegotiate_state_mut().send_pending = result.is_pending();
+        result
+    }
+
     pub(crate) fn poll_recv_raw(
         &mut self,
         buf_ptr: *mut libc::c_void,
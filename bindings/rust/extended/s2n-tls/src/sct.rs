@@ -0,0 +1,202 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for Certificate Transparency, as defined in
+//! [RFC 6962](https://www.rfc-editor.org/rfc/rfc6962).
+//!
+//! Servers provide a list of signed certificate timestamps (SCTs) for a certificate
+//! with [cert_chain::Builder::set_sct_list](`crate::cert_chain::Builder::set_sct_list()`).
+//! Clients request the list with
+//! [config::Builder::set_ct_support_level](`crate::config::Builder::set_ct_support_level()`),
+//! and retrieve it after the handshake with
+//! [Connection::sct_list](`crate::connection::Connection::sct_list()`).
+//!
+//! s2n-tls does not validate SCTs: applications are responsible for checking
+//! the signatures against the logs they trust.
+
+use crate::{codec::Reader, error::Error};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The only SCT version defined by RFC 6962.
+const V1: u8 = 0;
+
+/// A single SCT from a `SignedCertificateTimestampList`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedCertificateTimestamp {
+    log_id: [u8; 32],
+    timestamp: u64,
+    extensions: Vec<u8>,
+    hash_algorithm: u8,
+    signature_algorithm: u8,
+    signature: Vec<u8>,
+}
+
+impl SignedCertificateTimestamp {
+    /// Decodes a `SignedCertificateTimestampList`, such as the list returned by
+    /// [Connection::sct_list](`crate::connection::Connection::sct_list()`).
+    ///
+    /// SCTs with versions other than v1 can't be decoded, so are skipped.
+    pub fn parse_list(sct_list: &[u8]) -> Result<Vec<Self>, Error> {
+        let mut reader = Reader(sct_list);
+        let scts = reader.u16_prefixed()?.items(|list| {
+            let mut sct = list.u16_prefixed()?;
+            if sct.u8()? != V1 {
+                return Ok(None);
+            }
+
+            let mut log_id = [0; 32];
+            log_id.copy_from_slice(sct.bytes(32)?);
+            let timestamp = sct.u64()?;
+            let extensions = sct.u16_prefixed()?.0.to_vec();
+            let hash_algorithm = sct.u8()?;
+            let signature_algorithm = sct.u8()?;
+            let signature = sct.u16_prefixed()?.0.to_vec();
+            sct.finish(Some(Self {
+                log_id,
+                timestamp,
+                extensions,
+                hash_algorithm,
+                signature_algorithm,
+                signature,
+            }))
+        })?;
+        reader.finish(scts.into_iter().flatten().collect())
+    }
+
+    /// The SHA-256 hash of the public key of the log that issued the SCT.
+    pub fn log_id(&self) -> &[u8; 32] {
+        &self.log_id
+    }
+
+    /// The time at which the log issued the SCT.
+    pub fn timestamp(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.timestamp)
+    }
+
+    /// The raw `CtExtensions`, which are usually empty since RFC 6962 defines none.
+    pub fn extensions(&self) -> &[u8] {
+        &self.extensions
+    }
+
+    /// The TLS HashAlgorithm used for the signature, for example `4` for sha256.
+    pub fn hash_algorithm(&self) -> u8 {
+        self.hash_algorithm
+    }
+
+    /// The TLS SignatureAlgorithm used for the signature, for example `3` for ecdsa.
+    pub fn signature_algorithm(&self) -> u8 {
+        self.signature_algorithm
+    }
+
+    /// The log's signature over the certificate and SCT fields, for example DER-encoded for ecdsa.
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cert_chain, config,
+        enums::CtSupportLevel,
+        security::DEFAULT_TLS13,
+        testing::{CertKeyPair, InsecureAcceptAllCertificatesHandler, TestPair},
+    };
+
+    const LOG_ID: [u8; 32] = [7; 32];
+    const TIMESTAMP_MILLIS: u64 = 1_700_000_000_123;
+
+    fn test_sct(version: u8) -> Vec<u8> {
+        let mut sct = vec![version];
+        sct.extend_from_slice(&LOG_ID);
+        sct.extend_from_slice(&TIMESTAMP_MILLIS.to_be_bytes());
+        // no extensions
+        sct.extend_from_slice(&[0, 0]);
+        // sha256 + ecdsa, then a 3-byte signature
+        sct.extend_from_slice(&[4, 3, 0, 3, 1, 2, 3]);
+        sct
+    }
+
+    fn sct_list(scts: &[Vec<u8>]) -> Vec<u8> {
+        let mut list = vec![];
+        for sct in scts {
+            list.extend_from_slice(&(sct.len() as u16).to_be_bytes());
+            list.extend_from_slice(sct);
+        }
+        let mut prefixed = (list.len() as u16).to_be_bytes().to_vec();
+        prefixed.extend_from_slice(&list);
+        prefixed
+    }
+
+    #[test]
+    fn parse_list() -> Result<(), Error> {
+        let list = sct_list(&[test_sct(V1), test_sct(1), test_sct(V1)]);
+        let scts = SignedCertificateTimestamp::parse_list(&list)?;
+
+        // The unknown version is skipped
+        assert_eq!(scts.len(), 2);
+        let sct = &scts[0];
+        assert_eq!(sct.log_id(), &LOG_ID);
+        assert_eq!(
+            sct.timestamp(),
+            UNIX_EPOCH + Duration::from_millis(TIMESTAMP_MILLIS)
+        );
+        assert!(sct.extensions().is_empty());
+        assert_eq!(sct.hash_algorithm(), 4);
+        assert_eq!(sct.signature_algorithm(), 3);
+        assert_eq!(sct.signature(), [1, 2, 3]);
+
+        // Truncated lists fail
+        assert!(SignedCertificateTimestamp::parse_list(&list[..list.len() - 1]).is_err());
+        Ok(())
+    }
+
+    fn sct_pair(level: CtSupportLevel, sct_list: &[u8]) -> Result<TestPair, Error> {
+        let keypair = CertKeyPair::default();
+        let chain = {
+            let mut chain = cert_chain::Builder::new()?;
+            chain
+                .load_pem(keypair.cert(), keypair.key())?
+                .set_sct_list(sct_list)?;
+            chain.build()?
+        };
+
+        let mut server = config::Builder::new();
+        server
+            .set_security_policy(&DEFAULT_TLS13)?
+            .load_chain(chain)?;
+
+        let mut client = config::Builder::new();
+        client
+            .set_security_policy(&DEFAULT_TLS13)?
+            .set_ct_support_level(level)?
+            .trust_pem(keypair.cert())?
+            .set_verify_host_callback(InsecureAcceptAllCertificatesHandler {})?;
+
+        let mut pair = TestPair::from_configs(&client.build()?, &server.build()?);
+        pair.handshake()?;
+        Ok(pair)
+    }
+
+    #[test]
+    fn sct_requested() -> Result<(), Error> {
+        let list = sct_list(&[test_sct(V1)]);
+        let pair = sct_pair(CtSupportLevel::Request, &list)?;
+
+        let received = pair.client.sct_list().unwrap();
+        assert_eq!(received, list);
+        let scts = SignedCertificateTimestamp::parse_list(received)?;
+        assert_eq!(scts.len(), 1);
+        assert_eq!(scts[0].log_id(), &LOG_ID);
+        Ok(())
+    }
+
+    #[test]
+    fn sct_not_requested() -> Result<(), Error> {
+        let list = sct_list(&[test_sct(V1)]);
+        let pair = sct_pair(CtSupportLevel::None, &list)?;
+        assert_eq!(pair.client.sct_list(), None);
+        Ok(())
+    }
+}
@@ -7,7 +7,7 @@ use crate::{
     config::{self, *},
     connection,
     enums::{self, Blinding},
-    error, security,
+    error, init, security,
 };
 use alloc::{collections::VecDeque, sync::Arc};
 
@@ -41,6 +41,51 @@ pub fn assert_test_error(input: crate::error::Error, expected_message: &str) {
     assert_eq!(expected_message, error_msg.to_string())
 }
 
+/// A [`init::RandomSource`] that always returns the same bytes for the same seed.
+///
+/// Every random number generator seeded from the source generates the same
+/// values, so handshakes performed in the same order on new threads are
+/// byte-for-byte identical: see [`TestPair::handshake_transcript()`].
+///
+/// That is only true if s2n-tls overrides the libcrypto random number generator,
+/// so that key shares and signatures also come from the source. Check with
+/// [`DeterministicRandom::is_reproducible()`]: AWS-LC, OpenSSL 3, and
+/// FIPS mode are not supported.
+///
+/// It is NOT random, and must only be used for testing.
+pub struct DeterministicRandom(u64);
+
+impl DeterministicRandom {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Whether all random data generated by s2n-tls comes from the installed
+    /// [`init::RandomSource`], making handshakes reproducible.
+    ///
+    /// Initializes s2n-tls if it isn't already, so install the source first.
+    pub fn is_reproducible() -> bool {
+        init::init();
+        unsafe { s2n_tls_sys::s2n_supports_custom_rand() }
+    }
+}
+
+impl init::RandomSource for DeterministicRandom {
+    fn seed(&self, dest: &mut [u8]) -> Result<(), error::Error> {
+        // splitmix64: https://prng.di.unimi.it/splitmix64.c
+        let mut state = self.0;
+        for chunk in dest.chunks_mut(8) {
+            state = state.wrapping_add(0x9e3779b97f4a7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            z ^= z >> 31;
+            chunk.copy_from_slice(&z.to_le_bytes()[..chunk.len()]);
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone)]
 pub struct Counter(Arc<AtomicUsize>);
 impl Counter {
@@ -354,6 +399,32 @@ impl TestPair {
         }
     }
 
+    /// perform a TLS handshake between the connections, and record it
+    ///
+    /// Behaves like `handshake()`, but returns every byte sent by the client
+    /// and the server, in the order they were sent.
+    pub fn handshake_transcript(&mut self) -> Result<Vec<u8>, error::Error> {
+        let mut transcript = Vec::new();
+        loop {
+            // Only the peer reads from a connection's tx stream, so anything
+            // after the previous end of the stream was just sent.
+            let sent = self.io.client_tx_stream.borrow().len();
+            let client = self.client.poll_negotiate().map_ok(|_| ());
+            transcript.extend(self.io.client_tx_stream.borrow().iter().skip(sent));
+
+            let sent = self.io.server_tx_stream.borrow().len();
+            let server = self.server.poll_negotiate().map_ok(|_| ());
+            transcript.extend(self.io.server_tx_stream.borrow().iter().skip(sent));
+
+            match (client, server) {
+                (Poll::Ready(Ok(_)), Poll::Ready(Ok(_))) => return Ok(transcript),
+                (_, Poll::Ready(Err(e))) => return Err(e),
+                (Poll::Ready(Err(e)), _) => return Err(e),
+                _ => {}
+            }
+        }
+    }
+
     pub(crate) unsafe extern "C" fn send_cb(
         context: *mut c_void,
         data: *const u8,
@@ -7,10 +7,15 @@ mod tests {
         callbacks::{SessionTicket, SessionTicketCallback},
         config::ConnectionInitializer,
         connection::{self, Connection},
+        enums::HandshakeType,
         testing::*,
     };
     use futures_test::task::noop_waker;
-    use std::{error::Error, sync::Mutex, time::SystemTime};
+    use std::{
+        error::Error,
+        sync::Mutex,
+        time::{Duration, SystemTime},
+    };
 
     #[derive(Default, Clone)]
     pub struct SessionTicketHandler {
@@ -117,6 +122,57 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn resumption_handshake_flags() -> Result<(), Box<dyn Error>> {
+        let keypair = CertKeyPair::default();
+
+        let mut server_config_builder = Builder::new();
+        server_config_builder
+            .add_session_ticket_key(&KEYNAME, &KEY, SystemTime::now())?
+            .set_security_policy(&security::TESTING_TLS12)?
+            .load_pem(keypair.cert(), keypair.key())?;
+        let server_config = server_config_builder.build()?;
+
+        let handler = SessionTicketHandler::default();
+
+        let mut client_config_builder = Builder::new();
+        client_config_builder
+            .enable_session_tickets(true)?
+            .set_session_ticket_callback(handler.clone())?
+            .trust_pem(keypair.cert())?
+            .set_verify_host_callback(InsecureAcceptAllCertificatesHandler {})?
+            .set_security_policy(&security::TESTING_TLS12)?
+            .set_connection_initializer(handler)?;
+        let client_config = client_config_builder.build()?;
+
+        // initial handshake is a full handshake that issues a ticket
+        {
+            let mut pair = TestPair::from_configs(&client_config, &server_config);
+            pair.client.set_waker(Some(&noop_waker()))?;
+            pair.handshake()?;
+
+            let flags = pair.client.handshake_flags()?;
+            assert!(flags.is_full_handshake());
+            assert!(!flags.is_abbreviated());
+            assert!(flags.contains(HandshakeType::WITH_SESSION_TICKET));
+            assert_eq!(pair.server.handshake_flags()?, flags);
+        }
+
+        // second handshake resumes with the ticket
+        {
+            let mut pair = TestPair::from_configs(&client_config, &server_config);
+            pair.client.set_waker(Some(&noop_waker()))?;
+            pair.handshake()?;
+
+            let flags = pair.client.handshake_flags()?;
+            assert!(flags.is_abbreviated());
+            assert!(!flags.is_full_handshake());
+            assert_eq!(pair.server.handshake_flags()?, flags);
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn resume_tls13_session() -> Result<(), Box<dyn Error>> {
         let keypair = CertKeyPair::default();
@@ -164,4 +220,57 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn issue_tls13_tickets() -> Result<(), Box<dyn Error>> {
+        const LIFETIME: Duration = Duration::from_secs(60);
+
+        #[derive(Clone, Default)]
+        struct TicketCounter(Counter);
+        impl SessionTicketCallback for TicketCounter {
+            fn on_session_ticket(&self, _: &mut Connection, session_ticket: &SessionTicket) {
+                assert_eq!(session_ticket.lifetime().unwrap(), LIFETIME);
+                self.0.increment();
+            }
+        }
+
+        let keypair = CertKeyPair::default();
+        let mut server_config_builder = Builder::new();
+        server_config_builder
+            .add_session_ticket_key(&KEYNAME, &KEY, SystemTime::now())?
+            .set_initial_ticket_count(2)?
+            .set_session_state_lifetime(LIFETIME)?
+            .load_pem(keypair.cert(), keypair.key())?
+            .set_security_policy(&security::DEFAULT_TLS13)?;
+        let server_config = server_config_builder.build()?;
+
+        let tickets = TicketCounter::default();
+        let mut client_config_builder = Builder::new();
+        client_config_builder
+            .set_session_ticket_callback(tickets.clone())?
+            .trust_pem(keypair.cert())?
+            .set_verify_host_callback(InsecureAcceptAllCertificatesHandler {})?
+            .set_security_policy(&security::DEFAULT_TLS13)?;
+        let client_config = client_config_builder.build()?;
+
+        let mut pair = TestPair::from_configs(&client_config, &server_config);
+        pair.handshake()?;
+        assert!(pair.client.poll_recv(&mut [0]).is_pending());
+        assert_eq!(pair.server.tickets_sent()?, 2);
+        assert_eq!(tickets.0.count(), 2);
+
+        // Additional tickets are sent with the next application data
+        pair.server.add_new_tickets_to_send(3)?;
+        assert_eq!(pair.server.tickets_sent()?, 2);
+        assert!(pair.server.poll_send(b"x").is_ready());
+        let mut buf = [0; 1];
+        assert!(pair.client.poll_recv(&mut buf).is_ready());
+        assert_eq!(&buf, b"x");
+        assert_eq!(pair.server.tickets_sent()?, 5);
+        assert_eq!(tickets.0.count(), 5);
+
+        // Only servers send tickets
+        assert!(pair.client.tickets_sent().is_err());
+        Ok(())
+    }
 }
@@ -5,7 +5,10 @@
 mod tests {
     use crate::{
         callbacks::{ClientHelloCallback, ConnectionFuture, ConnectionFutureResult},
-        enums::ClientAuthType,
+        enums::{
+            CipherSuite, ClientAuthType, HandshakeType, MaxFragmentLength, NamedGroup,
+            SignatureScheme, Version,
+        },
         error::ErrorType,
         testing::{self, client_hello::*, Error, Result, *},
     };
@@ -59,6 +62,79 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn typed_cipher_suite_and_group() -> Result<(), Error> {
+        // PQ is supported
+        {
+            let policy = Policy::from_version("default_pq")?;
+            let mut pair = TestPair::from_config(&build_config(&policy)?);
+            pair.handshake()?;
+
+            let group = pair.client.selected_named_group().unwrap();
+            assert_eq!(group, NamedGroup::X25519_MLKEM768);
+            assert_eq!(group.iana_value(), 0x11EC);
+            assert!(group.is_post_quantum());
+            assert!(group.is_hybrid());
+            assert_eq!(pair.server.selected_named_group(), Some(group));
+
+            let suite = pair.client.selected_cipher_suite()?;
+            assert!(suite.is_tls13());
+            assert_eq!(
+                suite.iana_value().to_be_bytes(),
+                pair.client.cipher_suite_iana_value()?
+            );
+        }
+
+        // PQ isn't supported
+        {
+            let policy = Policy::from_version("20240501")?;
+            let mut pair = TestPair::from_config(&build_config(&policy)?);
+            pair.handshake()?;
+
+            let group = pair.client.selected_named_group().unwrap();
+            assert!(!group.is_post_quantum());
+            assert!(!group.is_hybrid());
+            assert!(!matches!(group, NamedGroup::Unknown(_)));
+        }
+
+        // RSA key exchange doesn't use a group
+        {
+            let policy = Policy::from_version("test_all_rsa_kex")?;
+            let mut pair = TestPair::from_config(&build_config(&policy)?);
+            pair.handshake()?;
+
+            assert_eq!(pair.client.selected_named_group(), None);
+            let suite = pair.client.selected_cipher_suite()?;
+            assert_eq!(suite, CipherSuite::TLS_RSA_WITH_AES_128_CBC_SHA);
+            assert!(!suite.is_tls13());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn named_group_iana_values() {
+        assert_eq!(NamedGroup::from(0x001D), NamedGroup::X25519);
+        assert_eq!(NamedGroup::from(0x0202), NamedGroup::MLKEM1024);
+        assert!(NamedGroup::MLKEM1024.is_post_quantum());
+        assert!(!NamedGroup::MLKEM1024.is_hybrid());
+
+        let unknown = NamedGroup::from(0xFFFF);
+        assert_eq!(unknown, NamedGroup::Unknown(0xFFFF));
+        assert_eq!(u16::from(unknown), 0xFFFF);
+        assert!(!unknown.is_post_quantum());
+
+        // s2n-tls only reports names, so groups without a variant have no value
+        assert_eq!(NamedGroup::from_name("x25519"), NamedGroup::X25519);
+        assert_eq!(NamedGroup::from_name("ffdhe2048"), NamedGroup::Unknown(0));
+
+        assert_eq!(
+            CipherSuite::from(0x1301),
+            CipherSuite::TLS_AES_128_GCM_SHA256
+        );
+        assert_eq!(CipherSuite::from(0x1304), CipherSuite::Unknown(0x1304));
+    }
+
     #[test]
     fn default_config_and_clone_interaction() -> Result<(), Error> {
         let config = build_config(&security::DEFAULT_TLS13)?;
@@ -611,6 +687,32 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn wire_bytes() -> Result<(), Error> {
+        let mut pair = TestPair::from_config(&build_config(&security::DEFAULT_TLS13)?);
+        assert_eq!(pair.client.wire_bytes_out(), 0);
+        assert_eq!(pair.server.wire_bytes_in(), 0);
+        pair.handshake()?;
+
+        let client_out = pair.client.wire_bytes_out();
+        let server_out = pair.server.wire_bytes_out();
+        assert!(client_out > 0);
+        assert!(server_out > 0);
+        assert_eq!(pair.server.wire_bytes_in(), client_out);
+        assert_eq!(pair.client.wire_bytes_in(), server_out);
+
+        let data = [1; 100];
+        assert!(pair.server.poll_send(&data).is_ready());
+        let mut buffer = [0; 100];
+        assert!(pair.client.poll_recv(&mut buffer).is_ready());
+
+        // Sent data includes the record header and encryption overhead
+        let sent = pair.server.wire_bytes_out() - server_out;
+        assert!(sent > data.len() as u64);
+        assert_eq!(pair.client.wire_bytes_in(), pair.server.wire_bytes_out());
+        Ok(())
+    }
+
     #[cfg(feature = "fips")]
     #[test]
     fn test_fips_mode() {
@@ -694,6 +796,240 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "unstable-npn")]
+    #[test]
+    fn npn() -> Result<(), Error> {
+        // NPN is not supported in TLS1.3
+        let mut client = config_builder(&security::TESTING_TLS12)?;
+        client
+            .set_application_protocol_preference(["spdy/3"])?
+            .enable_npn(true)?;
+        let mut server = config_builder(&security::TESTING_TLS12)?;
+        server
+            .set_application_protocol_preference(["h2"])?
+            .enable_npn(true)?;
+
+        // No protocol can be negotiated with ALPN, so the client chooses
+        // its most preferred protocol with NPN.
+        let mut pair = TestPair::from_configs(&client.build()?, &server.build()?);
+        pair.handshake()?;
+        assert_eq!(pair.client.application_protocol(), Some(&b"spdy/3"[..]));
+        assert_eq!(pair.server.application_protocol(), Some(&b"spdy/3"[..]));
+        Ok(())
+    }
+
+    #[cfg(feature = "unstable-npn")]
+    #[test]
+    fn npn_disabled() -> Result<(), Error> {
+        let mut client = config_builder(&security::TESTING_TLS12)?;
+        client
+            .set_application_protocol_preference(["spdy/3"])?
+            .enable_npn(true)?;
+        let mut server = config_builder(&security::TESTING_TLS12)?;
+        server.set_application_protocol_preference(["h2"])?;
+
+        let mut pair = TestPair::from_configs(&client.build()?, &server.build()?);
+        pair.handshake()?;
+        assert_eq!(pair.client.application_protocol(), None);
+        assert_eq!(pair.server.application_protocol(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn negotiated_parameters() -> Result<(), Error> {
+        let config = build_config(&security::DEFAULT_TLS13)?;
+        let mut pair = TestPair::from_config(&config);
+        pair.server.set_client_auth_type(ClientAuthType::Optional)?;
+        pair.handshake()?;
+
+        for conn in [&pair.client, &pair.server] {
+            assert_eq!(conn.client_protocol_version()?, Version::TLS13);
+            assert_eq!(conn.server_protocol_version()?, Version::TLS13);
+            assert_eq!(conn.last_message_name()?, "APPLICATION_DATA");
+            assert!(conn.session_id()?.is_empty());
+
+            // The default test certificate uses an RSA key,
+            // and TLS1.3 forbids PKCS#1 v1.5 signatures
+            let scheme = conn.signature_scheme()?.unwrap();
+            let expected = match scheme {
+                SignatureScheme::RSA_PSS_RSAE_SHA256 => 0x0804,
+                SignatureScheme::RSA_PSS_RSAE_SHA384 => 0x0805,
+                SignatureScheme::RSA_PSS_RSAE_SHA512 => 0x0806,
+                other => panic!("unexpected signature scheme {other:?}"),
+            };
+            assert_eq!(scheme.iana_value(), Some(expected));
+        }
+
+        let iana_value = pair.server.cipher_suite_iana_value()?;
+        assert_eq!(iana_value, pair.client.cipher_suite_iana_value()?);
+        let expected = match pair.server.cipher_suite()? {
+            "TLS_AES_128_GCM_SHA256" => [0x13, 0x01],
+            "TLS_AES_256_GCM_SHA384" => [0x13, 0x02],
+            "TLS_CHACHA20_POLY1305_SHA256" => [0x13, 0x03],
+            name => panic!("unexpected cipher suite {name}"),
+        };
+        assert_eq!(iana_value, expected);
+
+        assert_eq!(pair.server.client_auth_type()?, ClientAuthType::Optional);
+        assert_eq!(pair.client.client_auth_type()?, ClientAuthType::None);
+
+        // No session tickets were sent
+        assert!(pair.client.session_ticket_lifetime_hint().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn negotiated_parameters_tls12() -> Result<(), Error> {
+        let config = build_config(&security::TESTING_TLS12)?;
+        let mut pair = TestPair::from_config(&config);
+        pair.handshake()?;
+
+        assert_eq!(pair.client.actual_protocol_version()?, Version::TLS12);
+        assert_eq!(pair.server.server_protocol_version()?, Version::TLS12);
+        assert_eq!(pair.client.session_id()?, pair.server.session_id()?);
+
+        let scheme = pair.client.signature_scheme()?;
+        assert!(scheme.is_some());
+        assert_eq!(pair.server.signature_scheme()?, scheme);
+        Ok(())
+    }
+
+    #[test]
+    fn handshake_flags() -> Result<(), Error> {
+        // TLS1.3
+        {
+            let config = build_config(&security::DEFAULT_TLS13)?;
+            let mut pair = TestPair::from_config(&config);
+            assert_eq!(pair.server.handshake_flags()?, HandshakeType::empty());
+            pair.handshake()?;
+
+            let flags = pair.server.handshake_flags()?;
+            assert!(flags.is_full_handshake());
+            assert!(!flags.is_abbreviated());
+            assert!(!flags.is_client_authenticated());
+            assert!(!flags.is_hello_retry());
+            assert!(!flags.contains(HandshakeType::TLS12_PERFECT_FORWARD_SECRECY));
+            assert_eq!(pair.client.handshake_flags()?, flags);
+        }
+
+        // TLS1.2
+        {
+            let config = build_config(&security::TESTING_TLS12)?;
+            let mut pair = TestPair::from_config(&config);
+            pair.handshake()?;
+
+            let flags = pair.server.handshake_flags()?;
+            assert!(flags.is_full_handshake());
+            assert!(flags.contains(HandshakeType::TLS12_PERFECT_FORWARD_SECRECY));
+            assert!(!flags.contains(HandshakeType::MIDDLEBOX_COMPAT));
+        }
+
+        // Client auth requested, but no client certificate sent
+        {
+            let config = build_config(&security::DEFAULT_TLS13)?;
+            let mut pair = TestPair::from_config(&config);
+            pair.server.set_client_auth_type(ClientAuthType::Optional)?;
+            pair.handshake()?;
+
+            let flags = pair.server.handshake_flags()?;
+            assert!(flags.contains(HandshakeType::CLIENT_AUTH | HandshakeType::NO_CLIENT_CERT));
+            assert!(!flags.is_client_authenticated());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn handshake_flags_from_name() -> Result<(), Error> {
+        let name = "NEGOTIATED|FULL_HANDSHAKE|TLS12_PERFECT_FORWARD_SECRECY";
+        let flags = HandshakeType::from_name(name)?;
+        assert_eq!(
+            flags,
+            HandshakeType::NEGOTIATED
+                | HandshakeType::FULL_HANDSHAKE
+                | HandshakeType::TLS12_PERFECT_FORWARD_SECRECY
+        );
+        assert_eq!(
+            format!("{flags:?}"),
+            "HandshakeType(NEGOTIATED | FULL_HANDSHAKE | TLS12_PERFECT_FORWARD_SECRECY)"
+        );
+
+        let resumed = HandshakeType::from_name("NEGOTIATED|WITH_SESSION_TICKET")?;
+        assert!(resumed.is_abbreviated());
+        assert!(!resumed.is_full_handshake());
+
+        // TLS1.2 and TLS1.3 names for the same value are interchangeable
+        let tls12 = HandshakeType::from_name("NEGOTIATED|OCSP_STATUS")?;
+        let tls13 = HandshakeType::from_name("NEGOTIATED|MIDDLEBOX_COMPAT")?;
+        assert_eq!(tls12.for_version(Version::TLS13), tls13);
+        assert_eq!(tls13.for_version(Version::TLS12), tls12);
+        assert_eq!(tls13.for_version(Version::TLS13), tls13);
+
+        assert!(HandshakeType::from_name("INITIAL")?.is_empty());
+        assert!(HandshakeType::from_name("NEGOTIATED|UNKNOWN").is_err());
+        Ok(())
+    }
+
+    /// Sends `len` bytes from the server, and returns the lengths of the records sent.
+    fn server_record_lengths(pair: &mut TestPair, len: usize) -> Vec<usize> {
+        pair.io.server_tx_stream.borrow_mut().clear();
+        let data = vec![1; len];
+        assert!(pair.server.poll_send(&data).is_ready());
+
+        let sent: Vec<u8> = pair.io.server_tx_stream.borrow().iter().copied().collect();
+        let mut lengths = Vec::new();
+        let mut remaining = sent.as_slice();
+        while let [_, _, _, len_hi, len_lo, rest @ ..] = remaining {
+            let len = u16::from_be_bytes([*len_hi, *len_lo]) as usize;
+            remaining = &rest[len..];
+            lengths.push(len);
+        }
+        lengths
+    }
+
+    #[test]
+    fn max_fragment_length() -> Result<(), Error> {
+        let mut config = config_builder(&security::DEFAULT_TLS13)?;
+        config
+            .send_max_fragment_length(MaxFragmentLength::Len512)?
+            .accept_max_fragment_length()?;
+        let mut pair = TestPair::from_config(&config.build()?);
+        pair.handshake()?;
+
+        #[cfg(feature = "unstable-max_fragment_length")]
+        for conn in [&pair.client, &pair.server] {
+            assert_eq!(
+                conn.negotiated_max_fragment_length()?,
+                Some(MaxFragmentLength::Len512)
+            );
+        }
+
+        // Records can only be larger than their fragment by the encryption overhead
+        let lengths = server_record_lengths(&mut pair, 4000);
+        assert!(lengths.len() >= 4000 / MaxFragmentLength::Len512.size());
+        assert!(lengths.iter().all(|len| *len <= 512 + 256));
+        Ok(())
+    }
+
+    #[test]
+    fn max_fragment_length_not_accepted() -> Result<(), Error> {
+        let server_config = build_config(&security::DEFAULT_TLS13)?;
+        let mut client_config = config_builder(&security::DEFAULT_TLS13)?;
+        client_config.send_max_fragment_length(MaxFragmentLength::Len1024)?;
+
+        let mut pair = TestPair::from_configs(&client_config.build()?, &server_config);
+        pair.handshake()?;
+
+        #[cfg(feature = "unstable-max_fragment_length")]
+        for conn in [&pair.client, &pair.server] {
+            assert_eq!(conn.negotiated_max_fragment_length()?, None);
+        }
+
+        let lengths = server_record_lengths(&mut pair, 4000);
+        assert!(lengths.iter().any(|len| *len > 1024 + 256));
+        Ok(())
+    }
+
     #[test]
     fn client_hello_sslv2_negative() -> Result<(), testing::Error> {
         let config = testing::build_config(&security::DEFAULT_TLS13)?;
@@ -0,0 +1,42 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The random source must be installed before s2n-tls is initialized,
+//! so this test runs in its own process.
+
+#![cfg(feature = "unstable-testing")]
+
+use s2n_tls::{
+    error::Error,
+    init, security,
+    testing::{build_config, DeterministicRandom, TestPair},
+};
+
+/// Performs a handshake on a new thread, whose random number generators
+/// are freshly seeded from the source.
+fn transcript() -> Vec<u8> {
+    std::thread::spawn(|| {
+        let config = build_config(&security::DEFAULT_TLS13).unwrap();
+        TestPair::from_config(&config)
+            .handshake_transcript()
+            .unwrap()
+    })
+    .join()
+    .unwrap()
+}
+
+#[test]
+fn reproducible_transcripts() -> Result<(), Error> {
+    init::Builder::new()
+        .set_random_source(DeterministicRandom::new(1))
+        .init()?;
+    if !DeterministicRandom::is_reproducible() {
+        // Key shares and signatures are generated by libcrypto
+        return Ok(());
+    }
+
+    let first = transcript();
+    assert!(!first.is_empty());
+    assert_eq!(first, transcript());
+    Ok(())
+}
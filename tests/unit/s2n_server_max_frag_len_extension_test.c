@@ -13,6 +13,7 @@
  * permissions and limitations under the License.
  */
 
+#include "api/unstable/max_fragment_length.h"
 #include "s2n_test.h"
 #include "tls/extensions/s2n_server_max_fragment_length.h"
 #include "tls/s2n_tls.h"
@@ -70,6 +71,31 @@ int main(int argc, char **argv)
         EXPECT_SUCCESS(s2n_config_free(config));
     };
 
+    /* Test s2n_connection_get_negotiated_max_fragment_length */
+    {
+        DEFER_CLEANUP(struct s2n_connection *conn = s2n_connection_new(S2N_CLIENT), s2n_connection_ptr_free);
+        EXPECT_NOT_NULL(conn);
+
+        bool negotiated = true;
+        s2n_max_frag_len mfl_code = S2N_TLS_MAX_FRAG_LEN_4096;
+        EXPECT_FAILURE_WITH_ERRNO(s2n_connection_get_negotiated_max_fragment_length(NULL, &negotiated, &mfl_code),
+                S2N_ERR_NULL);
+        EXPECT_FAILURE_WITH_ERRNO(s2n_connection_get_negotiated_max_fragment_length(conn, NULL, &mfl_code),
+                S2N_ERR_NULL);
+        EXPECT_FAILURE_WITH_ERRNO(s2n_connection_get_negotiated_max_fragment_length(conn, &negotiated, NULL),
+                S2N_ERR_NULL);
+
+        /* Not negotiated by default */
+        EXPECT_SUCCESS(s2n_connection_get_negotiated_max_fragment_length(conn, &negotiated, &mfl_code));
+        EXPECT_FALSE(negotiated);
+        EXPECT_EQUAL(mfl_code, S2N_TLS_MAX_FRAG_LEN_4096);
+
+        conn->negotiated_mfl_code = S2N_TLS_MAX_FRAG_LEN_512;
+        EXPECT_SUCCESS(s2n_connection_get_negotiated_max_fragment_length(conn, &negotiated, &mfl_code));
+        EXPECT_TRUE(negotiated);
+        EXPECT_EQUAL(mfl_code, S2N_TLS_MAX_FRAG_LEN_512);
+    };
+
     /* Test receive - does not match requested value
      *
      *= https://www.rfc-editor.org/rfc/rfc6066#section-4
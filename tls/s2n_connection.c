@@ -27,6 +27,7 @@
 #include "api/s2n.h"
 /* Required for s2n_connection_get_key_update_counts */
 #include "api/unstable/ktls.h"
+#include "api/unstable/max_fragment_length.h"
 #include "crypto/s2n_certificate.h"
 #include "crypto/s2n_cipher.h"
 #include "crypto/s2n_crypto.h"
@@ -1385,6 +1386,20 @@ const uint8_t *s2n_connection_get_ocsp_response(struct s2n_connection *conn, uin
     return conn->status_response.data;
 }
 
+int s2n_connection_get_negotiated_max_fragment_length(struct s2n_connection *conn, bool *negotiated,
+        s2n_max_frag_len *mfl_code)
+{
+    POSIX_ENSURE_REF(conn);
+    POSIX_ENSURE_REF(negotiated);
+    POSIX_ENSURE_REF(mfl_code);
+
+    *negotiated = conn->negotiated_mfl_code != S2N_TLS_MAX_FRAG_LEN_EXT_NONE;
+    if (*negotiated) {
+        *mfl_code = conn->negotiated_mfl_code;
+    }
+    return S2N_SUCCESS;
+}
+
 S2N_RESULT s2n_connection_set_max_fragment_length(struct s2n_connection *conn, uint16_t max_frag_length)
 {
     RESULT_ENSURE_REF(conn);
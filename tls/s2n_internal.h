@@ -59,3 +59,12 @@ S2N_PRIVATE_API int s2n_config_add_cert_chain(struct s2n_config *config,
  * is still waiting for encryption.
  */
 S2N_PRIVATE_API int s2n_flush(struct s2n_connection *conn, s2n_blocked_status *blocked);
+
+/*
+ * Checks whether s2n-tls replaces the libcrypto random number generator with its own.
+ *
+ * If so, all random data used by s2n-tls, including key shares and signatures,
+ * is generated from the entropy provided by `s2n_rand_set_callbacks`.
+ * Only meaningful after `s2n_init`, because FIPS mode disables the replacement.
+ */
+S2N_PRIVATE_API bool s2n_supports_custom_rand(void);
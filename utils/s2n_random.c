@@ -75,6 +75,7 @@
 #include "error/s2n_errno.h"
 #include "s2n_io.h"
 #include "stuffer/s2n_stuffer.h"
+#include "tls/s2n_internal.h"
 #include "utils/s2n_fork_detection.h"
 #include "utils/s2n_init.h"
 #include "utils/s2n_mem.h"